@@ -0,0 +1,62 @@
+use std::env;
+use std::fs;
+use std::path::Path;
+
+// Checks that every src/yYYYY/dayNN.rs is declared in its year's mod.rs and
+// every year in lib.rs. A day left out would never register itself with the
+// solver registry, and nothing else would notice.
+fn main() {
+    let src_dir = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    let lib_path = src_dir.join("lib.rs");
+    println!("cargo:rerun-if-changed={}", src_dir.display());
+    println!("cargo:rerun-if-changed={}", lib_path.display());
+
+    let mut missing = Vec::new();
+    let lib = fs::read_to_string(&lib_path).unwrap();
+    for entry in fs::read_dir(&src_dir).unwrap() {
+        let path = entry.unwrap().path();
+        let year = path.file_name().unwrap().to_string_lossy().to_string();
+        if !path.is_dir() || !is_year_module(&year) {
+            continue;
+        }
+        println!("cargo:rerun-if-changed={}", path.display());
+        if !declares(&lib, &year) {
+            missing.push(format!("{} in {}", year, lib_path.display()));
+        }
+
+        let mod_path = path.join("mod.rs");
+        println!("cargo:rerun-if-changed={}", mod_path.display());
+        let declarations = fs::read_to_string(&mod_path).unwrap_or_default();
+        for entry in fs::read_dir(&path).unwrap() {
+            let path = entry.unwrap().path();
+            let day = path.file_stem().unwrap().to_string_lossy().to_string();
+            if path.extension().is_some_and(|e| e == "rs")
+                && is_day_module(&day)
+                && !declares(&declarations, &day)
+            {
+                missing.push(format!("{} in {}", day, mod_path.display()));
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        missing.sort();
+        panic!(
+            "modules without a `pub mod` declaration:\n    {}",
+            missing.join("\n    ")
+        );
+    }
+}
+
+fn declares(source: &str, module: &str) -> bool {
+    let declaration = format!("pub mod {};", module);
+    source.lines().any(|line| line.trim() == declaration)
+}
+
+fn is_year_module(name: &str) -> bool {
+    name.len() == 5 && name.starts_with('y') && name[1..].chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_day_module(name: &str) -> bool {
+    name.len() == 5 && name.starts_with("day") && name[3..].chars().all(|c| c.is_ascii_digit())
+}
@@ -0,0 +1,261 @@
+use crate::error::{Error, Result};
+use crate::inputs;
+use crate::inputs::Expected;
+use crate::registry;
+use crate::runner;
+use crate::solver::Part;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::time::Duration;
+use toml::Value;
+
+pub const ANSWERS_FILE_PATH: &str = "input/answers.toml";
+
+// Accepted answers stored as `[2020.day07]` tables with `part1`/`part2` string values
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: BTreeMap<(u16, u8), BTreeMap<u8, String>>,
+}
+
+impl Answers {
+    // A missing file is treated as an empty set of answers
+    pub fn load(path: &str) -> Result<Answers> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Answers::parse(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Answers> {
+        let value: Value = contents.parse()?;
+
+        let mut answers = Answers::default();
+        for (year_key, days) in table(&value, "the answers file")? {
+            let year = year_key
+                .parse()
+                .map_err(|_| Error::parse(format!("invalid year '{}'", year_key)))?;
+            for (day_key, parts) in table(days, year_key)? {
+                let day = day_key
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(|| Error::parse(format!("invalid day '{}'", day_key)))?;
+                for (part_key, answer) in table(parts, day_key)? {
+                    let part = part_from_key(part_key)
+                        .ok_or_else(|| Error::parse(format!("invalid part '{}'", part_key)))?;
+                    let answer = answer.as_str().ok_or_else(|| {
+                        Error::parse(format!(
+                            "answer for {}.{}.{} must be a string",
+                            year_key, day_key, part_key
+                        ))
+                    })?;
+                    answers.set(year, day, part, answer);
+                }
+            }
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: Part) -> Option<&str> {
+        self.answers
+            .get(&(year, day))
+            .and_then(|parts| parts.get(&part_number(part)))
+            .map(|a| a.as_str())
+    }
+
+    pub fn set(&mut self, year: u16, day: u8, part: Part, answer: &str) {
+        self.answers
+            .entry((year, day))
+            .or_default()
+            .insert(part_number(part), answer.to_string());
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.to_string()).map_err(|e| Error::io(path, e))
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, ((year, day), parts)) in self.answers.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}.day{:02}]", year, day)?;
+            for (part, answer) in parts {
+                writeln!(f, "part{} = {}", part, Value::String(answer.clone()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn table<'a>(value: &'a Value, name: &str) -> Result<&'a toml::value::Table> {
+    value
+        .as_table()
+        .ok_or_else(|| Error::parse(format!("expected '{}' to be a table", name)))
+}
+
+pub fn part_number(part: Part) -> u8 {
+    match part {
+        Part::One => 1,
+        Part::Two => 2,
+    }
+}
+
+pub fn part_from_key(key: &str) -> Option<Part> {
+    match key {
+        "part1" => Some(Part::One),
+        "part2" => Some(Part::Two),
+        _ => None,
+    }
+}
+
+// Solves every input of the implemented days and compares the answers with the
+// recorded ones, recording the answers found instead with `record`
+pub fn verify(year: Option<u16>, record: bool, timeout: Option<Duration>) -> Result<()> {
+    let mut answers = Answers::load(ANSWERS_FILE_PATH)?;
+    let entries: Vec<_> = registry::entries()
+        .into_iter()
+        .filter(|e| year.is_none_or(|y| e.year == y))
+        .collect();
+
+    let (mut passed, mut failed, mut missing, mut errors) = (0, 0, 0, 0);
+    for entry in entries {
+        for set in inputs::input_sets(entry.year, entry.day)? {
+            // Named inputs keep their answers next to them rather than in the answers file
+            let mut expected_named = match set.answers_path() {
+                Some(path) => Some((Expected::load(&path)?, path)),
+                None => None,
+            };
+            let parts = [Part::One, Part::Two];
+            let result = set
+                .load()
+                .map_err(|e| e.with_day(entry.year, entry.day))
+                .and_then(|input| {
+                    runner::run(
+                        (entry.solver)(),
+                        entry.year,
+                        entry.day,
+                        &input,
+                        &parts,
+                        timeout,
+                    )
+                });
+            // A parse failure is reported against both parts
+            let answers_found: Vec<std::result::Result<String, String>> = match result {
+                Ok(result) => result
+                    .parts
+                    .into_iter()
+                    .map(|p| p.answer.map_err(|e| e.to_string()))
+                    .collect(),
+                Err(error) => parts.iter().map(|_| Err(error.to_string())).collect(),
+            };
+
+            for (part, answer) in parts.iter().zip(answers_found) {
+                let mut name = format!("{} day {:02} part {}", entry.year, entry.day, part);
+                if !set.is_default() {
+                    name.push_str(&format!(" [{}]", set.name));
+                }
+                let expected = match &expected_named {
+                    Some((expected, _)) => expected.get(*part).map(String::from),
+                    None => answers.get(entry.year, entry.day, *part).map(String::from),
+                };
+                match answer {
+                    Ok(answer) => {
+                        match expected {
+                            Some(expected) if expected == answer => {
+                                passed += 1;
+                                println!("{}  PASS     {}", name, answer);
+                            }
+                            Some(expected) => {
+                                failed += 1;
+                                println!("{}  FAIL     {} (expected {})", name, answer, expected);
+                            }
+                            None => {
+                                missing += 1;
+                                println!("{}  MISSING  {}", name, answer);
+                            }
+                        }
+                        if record {
+                            match &mut expected_named {
+                                Some((expected, _)) => expected.set(*part, &answer),
+                                None => answers.set(entry.year, entry.day, *part, &answer),
+                            }
+                        }
+                    }
+                    Err(error) => {
+                        errors += 1;
+                        println!("{}  ERROR    {}", name, error);
+                    }
+                }
+            }
+            if let (true, Some((expected, path))) = (record, &expected_named) {
+                expected.save(path)?;
+            }
+        }
+    }
+
+    println!(
+        "{} passed, {} failed, {} missing, {} errors",
+        passed, failed, missing, errors
+    );
+    if record {
+        answers.save(ANSWERS_FILE_PATH)?;
+        println!("Recorded answers in {}", ANSWERS_FILE_PATH);
+    }
+
+    if errors > 0 || (!record && failed + missing > 0) {
+        return Err(Error::solve("verification failed"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::parse(
+            "[2015.day11]\npart1 = \"vzbxxyzz\"\n\n[2020.day07]\npart1 = \"179\"\npart2 = \"18925\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(answers.get(2015, 11, Part::One), Some("vzbxxyzz"));
+        assert_eq!(answers.get(2015, 11, Part::Two), None);
+        assert_eq!(answers.get(2020, 7, Part::Two), Some("18925"));
+    }
+
+    #[test]
+    fn test_parse_answers_error() {
+        let error = Answers::parse("[2020.day07]\npart1 = 179\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "answer for 2020.day07.part1 must be a string"
+        );
+
+        let error = Answers::parse("[2020.day07]\npart1 = \n").unwrap_err();
+        assert_eq!(error.line, Some(2));
+    }
+
+    #[test]
+    fn test_answers_round_trip() {
+        let mut answers = Answers::default();
+        answers.set(2020, 21, Part::Two, "rcqb,cltx");
+        answers.set(2015, 1, Part::One, "138");
+        answers.set(2020, 21, Part::One, "2659");
+
+        let text = answers.to_string();
+        assert_eq!(
+            text,
+            "[2015.day01]\npart1 = \"138\"\n\n[2020.day21]\npart1 = \"2659\"\npart2 = \"rcqb,cltx\"\n"
+        );
+        assert_eq!(
+            Answers::parse(&text).unwrap().get(2020, 21, Part::Two),
+            Some("rcqb,cltx")
+        );
+    }
+}
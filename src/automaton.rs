@@ -0,0 +1,244 @@
+use crate::grid::Grid;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::ops::RangeInclusive;
+
+// The numbers of live neighbours that bring a dead cell to life and that keep a
+// live cell alive. Conway's Game of Life is `birth: 3..=3, survive: 2..=3`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub birth: RangeInclusive<usize>,
+    pub survive: RangeInclusive<usize>,
+}
+
+impl Rule {
+    pub fn next(&self, alive: bool, live_neighbours: usize) -> bool {
+        if alive {
+            self.survive.contains(&live_neighbours)
+        } else {
+            self.birth.contains(&live_neighbours)
+        }
+    }
+}
+
+// A simulation where every cell of the next generation only depends on the cell
+// and its neighbours in the current one
+pub trait Automaton {
+    // Advances one generation, returning whether any cell changed
+    fn step(&mut self) -> bool;
+
+    fn live_cells(&self) -> usize;
+
+    fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    // Steps until a generation is the same as the one before, returning the
+    // number of generations that changed something
+    fn run_until_stable(&mut self) -> usize {
+        let mut generations = 0;
+        while self.step() {
+            generations += 1;
+        }
+        generations
+    }
+}
+
+// Keeps only the live cells, so the space can be unbounded and any type of
+// coordinate can be used. `neighbours` gives the neighbourhood of a cell.
+pub struct SparseAutomaton<C, N> {
+    live: HashSet<C>,
+    neighbours: N,
+    rule: Rule,
+}
+
+impl<C, N> SparseAutomaton<C, N>
+where
+    C: Clone + Eq + Hash,
+    N: Fn(&C) -> Vec<C>,
+{
+    pub fn new(live: HashSet<C>, neighbours: N, rule: Rule) -> SparseAutomaton<C, N> {
+        SparseAutomaton {
+            live,
+            neighbours,
+            rule,
+        }
+    }
+
+    pub fn cells(&self) -> &HashSet<C> {
+        &self.live
+    }
+
+    pub fn into_cells(self) -> HashSet<C> {
+        self.live
+    }
+}
+
+impl<C, N> Automaton for SparseAutomaton<C, N>
+where
+    C: Clone + Eq + Hash,
+    N: Fn(&C) -> Vec<C>,
+{
+    fn step(&mut self) -> bool {
+        // Only live cells and their neighbours can be alive next generation
+        let mut live_neighbours: HashMap<C, usize> =
+            self.live.iter().map(|cell| (cell.clone(), 0)).collect();
+        for cell in &self.live {
+            for neighbour in (self.neighbours)(cell) {
+                *live_neighbours.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        let live = live_neighbours
+            .into_iter()
+            .filter(|(cell, count)| self.rule.next(self.live.contains(cell), *count))
+            .map(|(cell, _)| cell)
+            .collect();
+        let changed = live != self.live;
+        self.live = live;
+        changed
+    }
+
+    fn live_cells(&self) -> usize {
+        self.live.len()
+    }
+}
+
+// Simulates every cell of a fixed grid. Positions holding `None` are not cells,
+// so they never change and are never counted as neighbours.
+pub struct DenseAutomaton {
+    cells: Grid<Option<bool>>,
+    neighbours: Grid<Vec<(usize, usize)>>,
+    rule: Rule,
+}
+
+impl DenseAutomaton {
+    // `neighbours` is only called once per position, as the layout of the cells
+    // never changes
+    pub fn new<N>(cells: Grid<Option<bool>>, neighbours: N, rule: Rule) -> DenseAutomaton
+    where
+        N: Fn(&Grid<Option<bool>>, usize, usize) -> Vec<(usize, usize)>,
+    {
+        let mut lists = Grid::new(cells.width(), cells.height(), Vec::new());
+        for (x, y) in cells.positions() {
+            if cells[(x, y)].is_some() {
+                lists[(x, y)] = neighbours(&cells, x, y)
+                    .into_iter()
+                    .filter(|&position| cells[position].is_some())
+                    .collect();
+            }
+        }
+
+        DenseAutomaton {
+            cells,
+            neighbours: lists,
+            rule,
+        }
+    }
+
+    pub fn cells(&self) -> &Grid<Option<bool>> {
+        &self.cells
+    }
+}
+
+impl Automaton for DenseAutomaton {
+    fn step(&mut self) -> bool {
+        let mut changed = false;
+        let mut next = self.cells.clone();
+        for (x, y) in self.cells.positions() {
+            if let Some(alive) = self.cells[(x, y)] {
+                let live_neighbours = self.neighbours[(x, y)]
+                    .iter()
+                    .filter(|&&position| self.cells[position] == Some(true))
+                    .count();
+                let next_alive = self.rule.next(alive, live_neighbours);
+                if next_alive != alive {
+                    next[(x, y)] = Some(next_alive);
+                    changed = true;
+                }
+            }
+        }
+
+        self.cells = next;
+        changed
+    }
+
+    fn live_cells(&self) -> usize {
+        self.cells.iter().filter(|c| **c == Some(true)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::automaton::*;
+
+    fn life() -> Rule {
+        Rule {
+            birth: 3..=3,
+            survive: 2..=3,
+        }
+    }
+
+    fn neighbours((x, y): &(isize, isize)) -> Vec<(isize, isize)> {
+        crate::grid::DIRECTIONS_8
+            .iter()
+            .map(|(dx, dy)| (x + dx, y + dy))
+            .collect()
+    }
+
+    #[test]
+    fn test_sparse_automaton() {
+        // A glider moves one cell diagonally every 4 generations
+        let glider = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+        let mut automaton =
+            SparseAutomaton::new(glider.iter().copied().collect(), neighbours, life());
+        automaton.run(4);
+        assert_eq!(
+            automaton.cells(),
+            &glider.iter().map(|(x, y)| (x + 1, y + 1)).collect()
+        );
+
+        // A block never changes
+        let block = [(0, 0), (1, 0), (0, 1), (1, 1)];
+        let mut automaton =
+            SparseAutomaton::new(block.iter().copied().collect(), neighbours, life());
+        assert_eq!(automaton.run_until_stable(), 0);
+        assert_eq!(automaton.live_cells(), 4);
+    }
+
+    #[test]
+    fn test_dense_automaton() {
+        // A blinker between two positions that are not cells
+        let cells = Grid::parse("x...x\n.###.\n.....\n", "", |c| match c {
+            '#' => Some(Some(true)),
+            '.' => Some(Some(false)),
+            _ => Some(None),
+        })
+        .unwrap();
+        let mut automaton =
+            DenseAutomaton::new(cells, |grid, x, y| grid.neighbours8(x, y).collect(), life());
+        assert!(automaton.step());
+        assert_eq!(
+            automaton.cells().render(|c| match c {
+                Some(true) => '#',
+                Some(false) => '.',
+                None => 'x',
+            }),
+            "x.#.x\n..#..\n..#..\n"
+        );
+
+        let rule = Rule {
+            birth: 9..=9,
+            survive: 0..=8,
+        };
+        let mut automaton = DenseAutomaton::new(
+            automaton.cells().clone(),
+            |grid, x, y| grid.neighbours8(x, y).collect(),
+            rule,
+        );
+        assert_eq!(automaton.run_until_stable(), 0);
+        assert_eq!(automaton.live_cells(), 3);
+    }
+}
@@ -0,0 +1,324 @@
+use crate::error::{field, parse_field, Error, Result};
+use crate::memory::{format_bytes, AllocStats};
+use crate::registry;
+use crate::registry::Entry;
+use crate::runner;
+use crate::runner::format_duration;
+use crate::solver;
+use crate::solver::Part;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl Stage {
+    // Name used in baseline files, without spaces so each line splits into fields
+    fn key(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Solve(Part::One) => "part1",
+            Stage::Solve(Part::Two) => "part2",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Stage> {
+        match key {
+            "parse" => Some(Stage::Parse),
+            "part1" => Some(Stage::Solve(Part::One)),
+            "part2" => Some(Stage::Solve(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Solve(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn new(samples: &mut [Duration]) -> Stats {
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct BenchResult {
+    pub year: u16,
+    pub day: u8,
+    pub stage: Stage,
+    pub runs: usize,
+    pub stats: Stats,
+    // Allocations of the last run, when the counting allocator is installed
+    pub memory: Option<AllocStats>,
+}
+
+// Runs parse and both parts of a day `runs` times on the same input
+pub fn bench_day(entry: &Entry, input: &str, runs: usize) -> Result<Vec<BenchResult>> {
+    let solver = (entry.solver)();
+    let stages = [
+        Stage::Parse,
+        Stage::Solve(Part::One),
+        Stage::Solve(Part::Two),
+    ];
+    let mut samples: Vec<Vec<Duration>> = vec![Vec::new(); stages.len()];
+    let mut memory = vec![None; stages.len()];
+
+    for _ in 0..runs {
+        let result = runner::run(
+            solver.clone(),
+            entry.year,
+            entry.day,
+            input,
+            &[Part::One, Part::Two],
+            None,
+        )?;
+        samples[0].push(result.parse);
+        memory[0] = result.parse_memory;
+        for (i, part) in result.parts.into_iter().enumerate() {
+            part.answer?;
+            samples[i + 1].push(part.duration);
+            memory[i + 1] = part.memory;
+        }
+    }
+
+    Ok(stages
+        .iter()
+        .zip(samples.iter_mut())
+        .zip(memory)
+        .map(|((&stage, samples), memory)| BenchResult {
+            year: entry.year,
+            day: entry.day,
+            stage,
+            runs,
+            stats: Stats::new(samples),
+            memory,
+        })
+        .collect())
+}
+
+pub struct Baseline {
+    medians: HashMap<(u16, u8, Stage), Duration>,
+}
+
+impl Baseline {
+    pub fn load(path: &str) -> Result<Baseline> {
+        let contents = fs::read_to_string(path).map_err(|e| Error::io(path, e))?;
+        let mut medians = HashMap::new();
+        for (i, line) in contents.lines().enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let year = parse_field(i + 1, line, &fields, 0)?;
+            let day = parse_field(i + 1, line, &fields, 1)?;
+            let stage_key = field(i + 1, line, &fields, 2)?;
+            let stage = Stage::from_key(stage_key).ok_or_else(|| {
+                Error::at_token(
+                    i + 1,
+                    line,
+                    stage_key,
+                    format!("unknown stage '{}'", stage_key),
+                )
+            })?;
+            let nanos = parse_field(i + 1, line, &fields, 3)?;
+            medians.insert((year, day, stage), Duration::from_nanos(nanos));
+        }
+
+        Ok(Baseline { medians })
+    }
+
+    pub fn save(path: &str, results: &[BenchResult]) -> Result<()> {
+        let mut contents = String::from("# year day stage median_ns\n");
+        for result in results {
+            contents.push_str(&format!(
+                "{} {} {} {}\n",
+                result.year,
+                result.day,
+                result.stage.key(),
+                result.stats.median.as_nanos()
+            ));
+        }
+        fs::write(path, contents).map_err(|e| Error::io(path, e))
+    }
+
+    // Relative change of the median against the baseline, e.g. 0.25 for 25% slower
+    pub fn change(&self, result: &BenchResult) -> Option<f64> {
+        let baseline = self
+            .medians
+            .get(&(result.year, result.day, result.stage))?
+            .as_nanos();
+        if baseline == 0 {
+            return None;
+        }
+        Some(result.stats.median.as_nanos() as f64 / baseline as f64 - 1.0)
+    }
+}
+
+// Prints one row per stage and returns how many stages regressed past `threshold` percent
+pub fn print_table(results: &[BenchResult], baseline: Option<&Baseline>, threshold: f64) -> usize {
+    let mut regressions = 0;
+    let show_memory = results.iter().any(|r| r.memory.is_some());
+    let mut header = format!(
+        "{:<6}{:>4}  {:<8}{:>6}{:>12}{:>12}{:>12}",
+        "year", "day", "stage", "runs", "min", "median", "max"
+    );
+    if show_memory {
+        header.push_str(&format!(
+            "{:>10}{:>12}{:>12}",
+            "allocs", "allocated", "peak"
+        ));
+    }
+    if baseline.is_some() {
+        header.push_str(&format!("{:>11}", "change"));
+    }
+    println!("{}", header);
+    for result in results {
+        let mut row = format!(
+            "{:<6}{:>4}  {:<8}{:>6}{:>12}{:>12}{:>12}",
+            result.year,
+            format!("{:02}", result.day),
+            result.stage.to_string(),
+            result.runs,
+            format_duration(result.stats.min),
+            format_duration(result.stats.median),
+            format_duration(result.stats.max)
+        );
+        if show_memory {
+            let memory = result.memory.unwrap_or_default();
+            row.push_str(&format!(
+                "{:>10}{:>12}{:>12}",
+                memory.allocations,
+                format_bytes(memory.bytes),
+                format_bytes(memory.peak)
+            ));
+        }
+        if let Some(change) = baseline.and_then(|b| b.change(result)) {
+            row.push_str(&format!("{:>+10.1}%", change * 100.0));
+            if change * 100.0 > threshold {
+                row.push_str("  SLOWER");
+                regressions += 1;
+            }
+        }
+        println!("{}", row);
+    }
+    regressions
+}
+
+// Times every implemented day matching `year` and `day`, comparing with and
+// saving baselines. Fails when a stage regressed by more than `threshold` percent.
+pub fn run_bench(
+    year: Option<u16>,
+    day: Option<u8>,
+    runs: usize,
+    save: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+) -> Result<()> {
+    let baseline = baseline.map(|path| Baseline::load(&path)).transpose()?;
+    let entries: Vec<_> = registry::entries()
+        .into_iter()
+        .filter(|e| year.is_none_or(|y| e.year == y) && day.is_none_or(|d| e.day == d))
+        .collect();
+    if entries.is_empty() {
+        return Err(Error::usage("no implemented days match the selection"));
+    }
+
+    let mut results = Vec::new();
+    for entry in entries {
+        let input = solver::load_input(&entry.year, &entry.day)
+            .map_err(|e| e.with_day(entry.year, entry.day))?;
+        results.extend(bench_day(entry, &input, runs)?);
+    }
+
+    let regressions = print_table(&results, baseline.as_ref(), threshold);
+    if let Some(path) = save {
+        Baseline::save(&path, &results)?;
+    }
+    if regressions > 0 {
+        return Err(Error::solve(format!(
+            "{} stages are more than {}% slower than the baseline",
+            regressions, threshold
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bench::*;
+
+    fn result(stage: Stage, median: u64) -> BenchResult {
+        BenchResult {
+            year: 2020,
+            day: 15,
+            stage,
+            runs: 3,
+            stats: Stats::new(&mut [
+                Duration::from_nanos(median * 2),
+                Duration::from_nanos(median),
+                Duration::from_nanos(median / 2),
+            ]),
+            memory: None,
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::new(&mut [
+            Duration::from_millis(5),
+            Duration::from_millis(1),
+            Duration::from_millis(3),
+            Duration::from_millis(9),
+            Duration::from_millis(4),
+        ]);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(4));
+        assert_eq!(stats.max, Duration::from_millis(9));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let path = std::env::temp_dir().join("aoc-bench-baseline-test.txt");
+        let path = path.to_str().unwrap();
+        Baseline::save(
+            path,
+            &[
+                result(Stage::Parse, 1000),
+                result(Stage::Solve(Part::Two), 2000),
+            ],
+        )
+        .unwrap();
+
+        let baseline = Baseline::load(path).unwrap();
+        fs::remove_file(path).unwrap();
+
+        let change = baseline.change(&result(Stage::Solve(Part::Two), 3000));
+        assert_eq!(change, Some(0.5));
+        assert_eq!(
+            baseline.change(&result(Stage::Solve(Part::One), 3000)),
+            None
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use crate::answers::{part_from_key, part_number, table};
+use crate::error::{Error, Result};
+use crate::runner;
+use crate::runner::{DayResult, PartResult};
+use crate::solver::{DynSolver, Part};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use toml::Value;
+
+pub const CACHE_FILE_PATH: &str = "input/cache.toml";
+
+// Answers of earlier runs stored as `[2020.day23.<input hash>]` tables with the
+// solver version and `part1`/`part2` string values. Answers are only returned
+// for the same input and the same solver version.
+#[derive(Debug, Default)]
+pub struct Cache {
+    entries: BTreeMap<(u16, u8, String), CacheEntry>,
+    changed: bool,
+}
+
+#[derive(Debug, Default)]
+struct CacheEntry {
+    version: String,
+    answers: BTreeMap<u8, String>,
+}
+
+impl Cache {
+    // A missing file is treated as an empty cache
+    pub fn load(path: &str) -> Result<Cache> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Cache::parse(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Cache::default()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Cache> {
+        let value: Value = contents.parse()?;
+
+        let mut cache = Cache::default();
+        for (year_key, days) in table(&value, "the cache file")? {
+            let year = year_key
+                .parse()
+                .map_err(|_| Error::parse(format!("invalid year '{}'", year_key)))?;
+            for (day_key, inputs) in table(days, year_key)? {
+                let day = day_key
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(|| Error::parse(format!("invalid day '{}'", day_key)))?;
+                for (hash, values) in table(inputs, day_key)? {
+                    let mut entry = CacheEntry::default();
+                    for (key, value) in table(values, hash)? {
+                        let value = value.as_str().ok_or_else(|| {
+                            Error::parse(format!(
+                                "{}.{}.{}.{} must be a string",
+                                year_key, day_key, hash, key
+                            ))
+                        })?;
+                        match part_from_key(key) {
+                            Some(part) => {
+                                entry.answers.insert(part_number(part), value.to_string());
+                            }
+                            None if key == "version" => entry.version = value.to_string(),
+                            None => return Err(Error::parse(format!("invalid key '{}'", key))),
+                        }
+                    }
+                    cache.entries.insert((year, day, hash.clone()), entry);
+                }
+            }
+        }
+
+        Ok(cache)
+    }
+
+    pub fn get(&self, year: u16, day: u8, hash: &str, version: &str, part: Part) -> Option<&str> {
+        self.entries
+            .get(&(year, day, hash.to_string()))
+            .filter(|entry| entry.version == version)
+            .and_then(|entry| entry.answers.get(&part_number(part)))
+            .map(|a| a.as_str())
+    }
+
+    // Answers cached by another version of the solver are dropped
+    pub fn set(&mut self, year: u16, day: u8, hash: &str, version: &str, part: Part, answer: &str) {
+        let entry = self
+            .entries
+            .entry((year, day, hash.to_string()))
+            .or_default();
+        if entry.version != version {
+            entry.version = version.to_string();
+            entry.answers.clear();
+        }
+        let previous = entry.answers.insert(part_number(part), answer.to_string());
+        self.changed |= previous.as_deref() != Some(answer);
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.to_string()).map_err(|e| Error::io(path, e))
+    }
+}
+
+impl std::fmt::Display for Cache {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for (i, ((year, day, hash), entry)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}.day{:02}.{}]", year, day, hash)?;
+            writeln!(f, "version = {}", Value::String(entry.version.clone()))?;
+            for (part, answer) in &entry.answers {
+                writeln!(f, "part{} = {}", part, Value::String(answer.clone()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// 64-bit FNV-1a of the input, which stays the same across builds and platforms
+pub fn hash_input(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+// Removes the cache file, returning whether there was one
+pub fn clear(path: &str) -> Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(Error::io(path, e)),
+    }
+}
+
+// Like `runner::run`, but answers found in `cache` are returned without solving
+// the part, and the input is only parsed when some part is not cached. Correct
+// answers are added to the cache.
+pub fn run(
+    cache: &Mutex<Cache>,
+    solver: Arc<dyn DynSolver>,
+    year: u16,
+    day: u8,
+    input: &str,
+    parts: &[Part],
+    timeout: Option<Duration>,
+) -> Result<DayResult> {
+    let hash = hash_input(input);
+    let version = solver.version();
+    let cached: Vec<Option<String>> = {
+        let cache = cache.lock().unwrap();
+        parts
+            .iter()
+            .map(|&part| cache.get(year, day, &hash, version, part).map(String::from))
+            .collect()
+    };
+    let missing: Vec<Part> = parts
+        .iter()
+        .zip(&cached)
+        .filter(|(_, answer)| answer.is_none())
+        .map(|(&part, _)| part)
+        .collect();
+
+    let mut result = if missing.is_empty() {
+        DayResult {
+            parse: Duration::ZERO,
+            parse_memory: None,
+            parts: Vec::new(),
+        }
+    } else {
+        runner::run(solver, year, day, input, &missing, timeout)?
+    };
+    {
+        let mut cache = cache.lock().unwrap();
+        for part in &result.parts {
+            if let Ok(answer) = &part.answer {
+                cache.set(year, day, &hash, version, part.part, answer);
+            }
+        }
+    }
+
+    let mut solved = mem::take(&mut result.parts).into_iter();
+    result.parts = parts
+        .iter()
+        .zip(cached)
+        .filter_map(|(&part, answer)| match answer {
+            Some(answer) => Some(PartResult {
+                part,
+                answer: Ok(answer),
+                duration: Duration::ZERO,
+                memory: None,
+                cached: true,
+            }),
+            None => solved.next(),
+        })
+        .collect();
+    Ok(result)
+}
+
+// The cache shared by the threads of a run, `None` when caching is disabled
+pub fn open(no_cache: bool) -> Result<Option<Mutex<Cache>>> {
+    if no_cache {
+        return Ok(None);
+    }
+    Cache::load(CACHE_FILE_PATH).map(|cache| Some(Mutex::new(cache)))
+}
+
+// Writes the cache back when the run added answers to it
+pub fn save_changes(cache: Option<Mutex<Cache>>) -> Result<()> {
+    match cache.map(|cache| cache.into_inner().unwrap()) {
+        Some(cache) if cache.is_changed() => cache.save(CACHE_FILE_PATH),
+        _ => Ok(()),
+    }
+}
+
+// Removes the cache file and reports whether there was one
+pub fn clear_cache() -> Result<()> {
+    if clear(CACHE_FILE_PATH)? {
+        println!("Removed {}", CACHE_FILE_PATH);
+    } else {
+        println!("{} does not exist", CACHE_FILE_PATH);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cache::*;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let mut cache = Cache::default();
+        cache.set(2020, 23, "00ff", "1", Part::One, "28946753");
+        cache.set(2015, 4, "abcd", "2", Part::Two, "1038736");
+        assert!(cache.is_changed());
+
+        let text = cache.to_string();
+        assert_eq!(
+            text,
+            "[2015.day04.abcd]\nversion = \"2\"\npart2 = \"1038736\"\n\n\
+             [2020.day23.00ff]\nversion = \"1\"\npart1 = \"28946753\"\n"
+        );
+
+        let mut cache = Cache::parse(&text).unwrap();
+        assert!(!cache.is_changed());
+        assert_eq!(
+            cache.get(2020, 23, "00ff", "1", Part::One),
+            Some("28946753")
+        );
+        assert_eq!(cache.get(2020, 23, "00ff", "2", Part::One), None);
+        assert_eq!(cache.get(2020, 23, "0100", "1", Part::One), None);
+
+        cache.set(2015, 4, "abcd", "3", Part::One, "117946");
+        assert_eq!(cache.get(2015, 4, "abcd", "3", Part::Two), None);
+        assert_eq!(cache.get(2015, 4, "abcd", "3", Part::One), Some("117946"));
+    }
+
+    #[test]
+    fn test_hash_input() {
+        assert_eq!(hash_input(""), "cbf29ce484222325");
+        assert_eq!(hash_input("a"), "af63dc4c8601ec8c");
+        assert_ne!(hash_input("()())\n"), hash_input("()()(\n"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "y2015", feature = "y2020"))]
+    fn test_run_cached() {
+        let cache = Mutex::new(Cache::default());
+        let solver = Arc::new(crate::y2015::day01::Problem);
+
+        let result = run(&cache, solver.clone(), 2015, 1, "()())", &[Part::Two], None).unwrap();
+        assert!(!result.parts[0].cached);
+        assert_eq!(result.parts[0].answer.as_ref().unwrap(), "5");
+
+        // Part 2 comes from the cache while part 1 is still solved
+        let result = run(
+            &cache,
+            solver,
+            2015,
+            1,
+            "()())",
+            &[Part::One, Part::Two],
+            None,
+        )
+        .unwrap();
+        assert_eq!(result.parts[0].part, Part::One);
+        assert!(!result.parts[0].cached);
+        assert_eq!(result.parts[0].answer.as_ref().unwrap(), "-1");
+        assert!(result.parts[1].cached);
+        assert_eq!(result.parts[1].answer.as_ref().unwrap(), "5");
+
+        // Failures are never cached, so the error is reported again
+        let solver = Arc::new(crate::y2020::day01::Problem);
+        assert!(run(&cache, solver.clone(), 2020, 1, "x\n", &[Part::One], None).is_err());
+        assert!(run(&cache, solver, 2020, 1, "x\n", &[Part::One], None).is_err());
+    }
+}
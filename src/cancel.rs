@@ -0,0 +1,122 @@
+use crate::error::{Error, Result};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Time limits for solving a part. The runner solves each part on a worker thread
+// and stops waiting for it once the part has run out of time. The worker also
+// gets a token, so loops that call `check` stop early instead of running on in
+// the background after the part has timed out.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+// Fails once the current thread's token is cancelled. Without a token, as when a
+// solver is called directly from a test, it always succeeds.
+pub fn check() -> Result<()> {
+    let cancelled = CURRENT.with(|c| c.borrow().as_ref().is_some_and(|t| t.is_cancelled()));
+    if cancelled {
+        Err(Error::cancelled())
+    } else {
+        Ok(())
+    }
+}
+
+// Runs `f` with `token` as the current thread's token
+pub fn with_token<T>(token: &CancellationToken, f: impl FnOnce() -> T) -> T {
+    struct Restore(Option<CancellationToken>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            CURRENT.with(|c| *c.borrow_mut() = previous);
+        }
+    }
+
+    let previous = CURRENT.with(|c| c.borrow_mut().replace(token.clone()));
+    let _restore = Restore(previous);
+    f()
+}
+
+// Runs `f` on a worker thread with a token that is cancelled once `timeout` has
+// passed, returning `None` when `f` has not finished by then. The worker is not
+// waited for after that, so a solver that never calls `check` runs on until it
+// is done or the process exits.
+pub fn with_timeout<T, F>(timeout: Duration, f: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    let token = CancellationToken::new();
+    let (sender, receiver) = mpsc::channel();
+    {
+        let token = token.clone();
+        thread::spawn(move || {
+            // The receiver is gone when the part has already timed out
+            let _ = sender.send(with_token(&token, f));
+        });
+    }
+
+    match receiver.recv_timeout(timeout) {
+        Ok(result) => Some(result),
+        Err(_) => {
+            token.cancel();
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cancel::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_with_timeout() {
+        // The worker sees its token cancelled once the caller stops waiting
+        let (sender, receiver) = mpsc::channel();
+        let result = with_timeout(Duration::from_millis(20), move || {
+            let result: Result<()> = loop {
+                if let Err(error) = check() {
+                    break Err(error);
+                }
+                thread::sleep(Duration::from_millis(1));
+            };
+            sender.send(result).unwrap();
+        });
+        assert!(result.is_none());
+        let cancelled = receiver.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(cancelled.unwrap_err().to_string(), "cancelled");
+        assert!(check().is_ok());
+
+        // A worker that never checks is not waited for
+        let start = Instant::now();
+        let result = with_timeout(Duration::from_millis(20), || {
+            thread::sleep(Duration::from_secs(10));
+        });
+        assert!(result.is_none());
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        let result = with_timeout(Duration::from_secs(10), || check().map(|_| 42));
+        assert_eq!(result.unwrap().unwrap(), 42);
+    }
+}
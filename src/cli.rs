@@ -0,0 +1,529 @@
+use advent_of_code_rust::output::Format;
+use advent_of_code_rust::{Error, Part, Result};
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+usage: advent-of-code-rust <command> [options]
+
+commands:
+    run <year> <day> [--part 1|2] [--input PATH|-]  solve one day, reading stdin for '-'
+        [--format text|json|csv] [--timeout SECONDS] [--no-cache]
+    run-all [--year YEAR] [--jobs N]                solve every implemented day in parallel
+            [--format text|json|csv] [--timeout SECONDS] [--no-cache]
+    bench [--year YEAR] [--day DAY] [--runs N]      time every implemented day
+          [--save PATH] [--baseline PATH] [--threshold PERCENT]
+    verify [--year YEAR] [--record]                 check answers against input/answers.toml
+           [--timeout SECONDS]
+    fetch <year> <day>                              download a day's input unless already cached
+    submit <year> <day> <part>                      solve a part and submit its answer
+    new <year> <day>                                create a day's module and empty input file
+    watch <year> <day> [--interval SECONDS]         re-run a day whenever its input or examples change
+    cache clear                                     forget the answers cached by run and run-all
+    list                                            show the implemented days
+    help                                            show this message
+
+Besides input/<year>/dayNN.txt, run, run-all and verify use every named input
+input/<year>/dayNN/<name>.txt, checked against the answers in <name>.toml when
+it has them. run lists the answers of all of a day's inputs side by side.
+Answers cached in input/cache.toml are reused until the input or the day's
+solver version changes.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run {
+        year: u16,
+        day: u8,
+        part: Option<Part>,
+        input: Option<String>,
+        format: Format,
+        timeout: Option<Duration>,
+        no_cache: bool,
+    },
+    RunAll {
+        year: Option<u16>,
+        jobs: Option<usize>,
+        format: Format,
+        timeout: Option<Duration>,
+        no_cache: bool,
+    },
+    Bench {
+        year: Option<u16>,
+        day: Option<u8>,
+        runs: usize,
+        save: Option<String>,
+        baseline: Option<String>,
+        threshold: f64,
+    },
+    Verify {
+        year: Option<u16>,
+        record: bool,
+        timeout: Option<Duration>,
+    },
+    Fetch {
+        year: u16,
+        day: u8,
+    },
+    Submit {
+        year: u16,
+        day: u8,
+        part: Part,
+    },
+    New {
+        year: u16,
+        day: u8,
+    },
+    Watch {
+        year: u16,
+        day: u8,
+        interval: Duration,
+    },
+    CacheClear,
+    List,
+    Help,
+}
+
+pub fn parse_args(args: &[String]) -> Result<Command> {
+    let (command, rest) = match args.split_first() {
+        Some((command, rest)) => (command.as_str(), rest),
+        None => return Err(Error::usage("missing command")),
+    };
+
+    let mut args = Args::new(command, rest);
+    let command = match command {
+        "run" => {
+            let year = parse_year(&args.positional("year")?)?;
+            let day = parse_day(&args.positional("day")?)?;
+            let mut part = None;
+            let mut input = None;
+            let mut format = Format::Text;
+            let mut timeout = None;
+            let mut no_cache = false;
+            while let Some(flag) = args.next_flag()? {
+                match flag.as_str() {
+                    "--part" => part = Some(parse_part(&args.value(&flag)?)?),
+                    "--input" => input = Some(args.value(&flag)?),
+                    "--format" => format = parse_format(&args.value(&flag)?)?,
+                    "--timeout" => timeout = Some(parse_seconds(&flag, &args.value(&flag)?)?),
+                    "--no-cache" => no_cache = true,
+                    _ => return Err(args.unknown_flag(&flag)),
+                }
+            }
+            Command::Run {
+                year,
+                day,
+                part,
+                input,
+                format,
+                timeout,
+                no_cache,
+            }
+        }
+        "run-all" => {
+            let mut year = None;
+            let mut jobs = None;
+            let mut format = Format::Text;
+            let mut timeout = None;
+            let mut no_cache = false;
+            while let Some(flag) = args.next_flag()? {
+                match flag.as_str() {
+                    "--year" => year = Some(parse_year(&args.value(&flag)?)?),
+                    "--jobs" => jobs = Some(parse_positive(&flag, &args.value(&flag)?)?),
+                    "--format" => format = parse_format(&args.value(&flag)?)?,
+                    "--timeout" => timeout = Some(parse_seconds(&flag, &args.value(&flag)?)?),
+                    "--no-cache" => no_cache = true,
+                    _ => return Err(args.unknown_flag(&flag)),
+                }
+            }
+            Command::RunAll {
+                year,
+                jobs,
+                format,
+                timeout,
+                no_cache,
+            }
+        }
+        "bench" => {
+            let mut year = None;
+            let mut day = None;
+            let mut runs = 10;
+            let mut save = None;
+            let mut baseline = None;
+            let mut threshold = 10.0;
+            while let Some(flag) = args.next_flag()? {
+                match flag.as_str() {
+                    "--year" => year = Some(parse_year(&args.value(&flag)?)?),
+                    "--day" => day = Some(parse_day(&args.value(&flag)?)?),
+                    "--runs" => runs = parse_positive(&flag, &args.value(&flag)?)?,
+                    "--save" => save = Some(args.value(&flag)?),
+                    "--baseline" => baseline = Some(args.value(&flag)?),
+                    "--threshold" => threshold = parse_percent(&args.value(&flag)?)?,
+                    _ => return Err(args.unknown_flag(&flag)),
+                }
+            }
+            Command::Bench {
+                year,
+                day,
+                runs,
+                save,
+                baseline,
+                threshold,
+            }
+        }
+        "verify" => {
+            let mut year = None;
+            let mut record = false;
+            let mut timeout = None;
+            while let Some(flag) = args.next_flag()? {
+                match flag.as_str() {
+                    "--year" => year = Some(parse_year(&args.value(&flag)?)?),
+                    "--record" => record = true,
+                    "--timeout" => timeout = Some(parse_seconds(&flag, &args.value(&flag)?)?),
+                    _ => return Err(args.unknown_flag(&flag)),
+                }
+            }
+            Command::Verify {
+                year,
+                record,
+                timeout,
+            }
+        }
+        "fetch" => {
+            let year = parse_year(&args.positional("year")?)?;
+            let day = parse_day(&args.positional("day")?)?;
+            Command::Fetch { year, day }
+        }
+        "submit" => {
+            let year = parse_year(&args.positional("year")?)?;
+            let day = parse_day(&args.positional("day")?)?;
+            let part = parse_part(&args.positional("part")?)?;
+            Command::Submit { year, day, part }
+        }
+        "new" => {
+            let year = parse_year(&args.positional("year")?)?;
+            let day = parse_day(&args.positional("day")?)?;
+            Command::New { year, day }
+        }
+        "watch" => {
+            let year = parse_year(&args.positional("year")?)?;
+            let day = parse_day(&args.positional("day")?)?;
+            let mut interval = Duration::from_millis(500);
+            while let Some(flag) = args.next_flag()? {
+                match flag.as_str() {
+                    "--interval" => interval = parse_seconds(&flag, &args.value(&flag)?)?,
+                    _ => return Err(args.unknown_flag(&flag)),
+                }
+            }
+            Command::Watch {
+                year,
+                day,
+                interval,
+            }
+        }
+        "cache" => match args.positional("action")?.as_str() {
+            "clear" => Command::CacheClear,
+            action => {
+                return Err(Error::usage(format!(
+                    "unknown cache action '{}', expected clear",
+                    action
+                )))
+            }
+        },
+        "list" => Command::List,
+        "help" | "--help" | "-h" => Command::Help,
+        _ => return Err(Error::usage(format!("unknown command '{}'", command))),
+    };
+
+    args.finish()?;
+    Ok(command)
+}
+
+struct Args<'a> {
+    command: &'a str,
+    args: std::slice::Iter<'a, String>,
+}
+
+impl<'a> Args<'a> {
+    fn new(command: &'a str, args: &'a [String]) -> Args<'a> {
+        Args {
+            command,
+            args: args.iter(),
+        }
+    }
+
+    fn positional(&mut self, name: &str) -> Result<String> {
+        match self.args.next() {
+            Some(arg) if !arg.starts_with("--") => Ok(arg.to_string()),
+            _ => Err(Error::usage(format!(
+                "'{}' requires a <{}> argument",
+                self.command, name
+            ))),
+        }
+    }
+
+    fn next_flag(&mut self) -> Result<Option<String>> {
+        match self.args.next() {
+            Some(arg) if arg.starts_with("--") => Ok(Some(arg.to_string())),
+            Some(arg) => Err(Error::usage(format!("unexpected argument '{}'", arg))),
+            None => Ok(None),
+        }
+    }
+
+    fn value(&mut self, flag: &str) -> Result<String> {
+        self.args
+            .next()
+            .map(|v| v.to_string())
+            .ok_or_else(|| Error::usage(format!("'{}' requires a value", flag)))
+    }
+
+    fn unknown_flag(&self, flag: &str) -> Error {
+        Error::usage(format!("unknown option '{}' for '{}'", flag, self.command))
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        match self.args.next() {
+            Some(arg) => Err(Error::usage(format!("unexpected argument '{}'", arg))),
+            None => Ok(()),
+        }
+    }
+}
+
+fn parse_year(value: &str) -> Result<u16> {
+    match value.parse::<u16>() {
+        Ok(year) if year >= 2015 => Ok(year),
+        _ => Err(Error::usage(format!(
+            "invalid year '{}', expected 2015 or later",
+            value
+        ))),
+    }
+}
+
+fn parse_day(value: &str) -> Result<u8> {
+    match value.parse::<u8>() {
+        Ok(day) if (1..=25).contains(&day) => Ok(day),
+        _ => Err(Error::usage(format!(
+            "invalid day '{}', expected 1-25",
+            value
+        ))),
+    }
+}
+
+fn parse_part(value: &str) -> Result<Part> {
+    match value {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(Error::usage(format!(
+            "invalid part '{}', expected 1 or 2",
+            value
+        ))),
+    }
+}
+
+fn parse_format(value: &str) -> Result<Format> {
+    match value {
+        "text" => Ok(Format::Text),
+        "json" => Ok(Format::Json),
+        "csv" => Ok(Format::Csv),
+        _ => Err(Error::usage(format!(
+            "invalid format '{}', expected text, json or csv",
+            value
+        ))),
+    }
+}
+
+fn parse_seconds(flag: &str, value: &str) -> Result<Duration> {
+    let duration = value
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok());
+    match duration {
+        Some(duration) if !duration.is_zero() => Ok(duration),
+        _ => Err(Error::usage(format!(
+            "invalid value '{}' for '{}', expected a positive number of seconds",
+            value, flag
+        ))),
+    }
+}
+
+fn parse_positive(flag: &str, value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(Error::usage(format!(
+            "invalid value '{}' for '{}', expected a positive number",
+            value, flag
+        ))),
+    }
+}
+
+fn parse_percent(value: &str) -> Result<f64> {
+    match value.trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if percent >= 0.0 => Ok(percent),
+        _ => Err(Error::usage(format!(
+            "invalid threshold '{}', expected a percentage",
+            value
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::*;
+
+    fn parse(args: &str) -> Result<Command> {
+        let args: Vec<String> = args.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    #[test]
+    fn test_parse_run() {
+        assert_eq!(
+            parse("run 2020 7").unwrap(),
+            Command::Run {
+                year: 2020,
+                day: 7,
+                part: None,
+                input: None,
+                format: Format::Text,
+                timeout: None,
+                no_cache: false
+            }
+        );
+        assert_eq!(
+            parse("run 2015 13 --input example.txt --part 2 --format json --timeout 1.5").unwrap(),
+            Command::Run {
+                year: 2015,
+                day: 13,
+                part: Some(Part::Two),
+                input: Some(String::from("example.txt")),
+                format: Format::Json,
+                timeout: Some(Duration::from_millis(1500)),
+                no_cache: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_run_all_and_list() {
+        assert_eq!(
+            parse("run-all").unwrap(),
+            Command::RunAll {
+                year: None,
+                jobs: None,
+                format: Format::Text,
+                timeout: None,
+                no_cache: false
+            }
+        );
+        assert_eq!(
+            parse("run-all --jobs 4 --year 2015 --format csv --no-cache").unwrap(),
+            Command::RunAll {
+                year: Some(2015),
+                jobs: Some(4),
+                format: Format::Csv,
+                timeout: None,
+                no_cache: true
+            }
+        );
+        assert_eq!(parse("cache clear").unwrap(), Command::CacheClear);
+        assert_eq!(parse("list").unwrap(), Command::List);
+        assert_eq!(
+            parse("fetch 2020 1").unwrap(),
+            Command::Fetch { year: 2020, day: 1 }
+        );
+        assert_eq!(
+            parse("watch 2020 20 --interval 2").unwrap(),
+            Command::Watch {
+                year: 2020,
+                day: 20,
+                interval: Duration::from_secs(2)
+            }
+        );
+        assert_eq!(
+            parse("new 2016 1").unwrap(),
+            Command::New { year: 2016, day: 1 }
+        );
+        assert_eq!(
+            parse("submit 2015 4 2").unwrap(),
+            Command::Submit {
+                year: 2015,
+                day: 4,
+                part: Part::Two
+            }
+        );
+        assert_eq!(
+            parse("verify --record").unwrap(),
+            Command::Verify {
+                year: None,
+                record: true,
+                timeout: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_bench() {
+        assert_eq!(
+            parse("bench --year 2020 --runs 3 --baseline base.txt --threshold 25%").unwrap(),
+            Command::Bench {
+                year: Some(2020),
+                day: None,
+                runs: 3,
+                save: None,
+                baseline: Some(String::from("base.txt")),
+                threshold: 25.0
+            }
+        );
+        assert_eq!(
+            parse("bench --runs 0").unwrap_err().to_string(),
+            "invalid value '0' for '--runs', expected a positive number"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse("").unwrap_err().to_string(), "missing command");
+        assert_eq!(
+            parse("2020 1").unwrap_err().to_string(),
+            "unknown command '2020'"
+        );
+        assert_eq!(
+            parse("run 2020").unwrap_err().to_string(),
+            "'run' requires a <day> argument"
+        );
+        assert_eq!(
+            parse("run 2020 x").unwrap_err().to_string(),
+            "invalid day 'x', expected 1-25"
+        );
+        assert_eq!(
+            parse("run 2020 1 --part 3").unwrap_err().to_string(),
+            "invalid part '3', expected 1 or 2"
+        );
+        assert_eq!(
+            parse("run 2020 1 --input").unwrap_err().to_string(),
+            "'--input' requires a value"
+        );
+        assert_eq!(
+            parse("cache drop").unwrap_err().to_string(),
+            "unknown cache action 'drop', expected clear"
+        );
+        assert_eq!(
+            parse("run 2020 1 --format xml").unwrap_err().to_string(),
+            "invalid format 'xml', expected text, json or csv"
+        );
+        assert_eq!(
+            parse("run-all --timeout 0").unwrap_err().to_string(),
+            "invalid value '0' for '--timeout', expected a positive number of seconds"
+        );
+        assert_eq!(
+            parse("run 2020 1 --timeout 1e30").unwrap_err().to_string(),
+            "invalid value '1e30' for '--timeout', expected a positive number of seconds"
+        );
+        assert_eq!(
+            parse("run-all --day 1").unwrap_err().to_string(),
+            "unknown option '--day' for 'run-all'"
+        );
+        assert_eq!(
+            parse("list 2020").unwrap_err().to_string(),
+            "unexpected argument '2020'"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::solver::Part;
+use std::time::Duration;
+use ureq::Agent;
+
+const USER_AGENT: &str = concat!("advent-of-code-rust/", env!("CARGO_PKG_VERSION"));
+
+// HTTP client for the puzzle server, authenticated with the session cookie
+pub struct Client {
+    agent: Agent,
+    base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(config: &Config) -> Result<Client> {
+        Ok(Client {
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .user_agent(USER_AGENT)
+                .build(),
+            base_url: config.base_url.trim_end_matches('/').to_string(),
+            session: config.session()?.to_string(),
+        })
+    }
+
+    pub fn get_input(&self, year: u16, day: u8) -> Result<String> {
+        let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+        read_response(&url, response)
+    }
+
+    // Returns the HTML page describing whether the answer was accepted
+    pub fn post_answer(&self, year: u16, day: u8, part: Part, answer: &str) -> Result<String> {
+        let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)]);
+        read_response(&url, response)
+    }
+}
+
+fn read_response(
+    url: &str,
+    response: std::result::Result<ureq::Response, ureq::Error>,
+) -> Result<String> {
+    match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|e| Error::http(format!("unable to read the response from {}: {}", url, e))),
+        Err(ureq::Error::Status(status, response)) => {
+            let body = response.into_string().unwrap_or_default();
+            let message = body.lines().next().unwrap_or("").trim();
+            Err(Error::http(format!(
+                "{} returned status {}{}{}",
+                url,
+                status,
+                if message.is_empty() { "" } else { ": " },
+                message
+            )))
+        }
+        Err(error) => Err(Error::http(format!("unable to reach {}: {}", url, error))),
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use crate::client::*;
+    use std::io::prelude::*;
+    use std::io::BufReader;
+    use std::net::TcpListener;
+    use std::thread;
+    use std::thread::JoinHandle;
+
+    // Stand-in server answering a single request with `status` and `body`,
+    // returning its base URL and a handle yielding the raw request it received
+    pub fn serve_once(status: u16, body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            request
+        });
+        (base_url, handle)
+    }
+
+    pub fn config(base_url: &str) -> Config {
+        Config {
+            session: Some(String::from("53616c7465")),
+            base_url: base_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_get_input() {
+        let (base_url, server) = serve_once(200, "1721\n979\n");
+        let client = Client::new(&config(&base_url)).unwrap();
+
+        assert_eq!(client.get_input(2020, 1).unwrap(), "1721\n979\n");
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2020/day/1/input HTTP/1.1\r\n"));
+        assert!(request.contains("\r\nCookie: session=53616c7465\r\n"));
+    }
+
+    #[test]
+    fn test_post_answer() {
+        let (base_url, server) =
+            serve_once(200, "<article><p>That's the right answer!</p></article>");
+        let client = Client::new(&config(&base_url)).unwrap();
+
+        let page = client.post_answer(2020, 1, Part::Two, "241861950").unwrap();
+        assert!(page.contains("right answer"));
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /2020/day/1/answer HTTP/1.1\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=241861950"));
+    }
+
+    #[test]
+    fn test_get_input_error() {
+        let (base_url, server) = serve_once(
+            404,
+            "Please don't repeatedly request this endpoint before it unlocks!\n",
+        );
+        let client = Client::new(&config(&base_url)).unwrap();
+
+        let error = client.get_input(2020, 25).unwrap_err();
+        server.join().unwrap();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "{}/2020/day/25/input returned status 404: Please don't repeatedly request this endpoint before it unlocks!",
+                base_url
+            )
+        );
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::{Error, Result};
+use std::env;
+use std::fs;
+use std::io;
+use toml::Value;
+
+pub const CONFIG_FILE_PATH: &str = "aoc.toml";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+// Settings for talking to the puzzle server, read from `aoc.toml` and
+// overridden by the `AOC_SESSION` and `AOC_BASE_URL` environment variables
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: String,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            session: None,
+            base_url: DEFAULT_BASE_URL.to_string(),
+        }
+    }
+}
+
+impl Config {
+    // A missing file leaves the defaults in place
+    pub fn load(path: &str) -> Result<Config> {
+        let mut config = match fs::read_to_string(path) {
+            Ok(contents) => Config::parse(&contents)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Config::default(),
+            Err(e) => return Err(Error::io(path, e)),
+        };
+
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session.trim().to_string());
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = base_url;
+        }
+        Ok(config)
+    }
+
+    pub fn parse(contents: &str) -> Result<Config> {
+        let value: Value = contents.parse()?;
+
+        let mut config = Config::default();
+        if let Some(session) = string(&value, "session")? {
+            config.session = Some(session.trim().to_string());
+        }
+        if let Some(base_url) = string(&value, "base_url")? {
+            config.base_url = base_url.to_string();
+        }
+        Ok(config)
+    }
+
+    pub fn session(&self) -> Result<&str> {
+        match &self.session {
+            Some(session) if !session.is_empty() => Ok(session),
+            _ => Err(Error::usage(format!(
+                "no session token, set 'session' in {} or the AOC_SESSION environment variable",
+                CONFIG_FILE_PATH
+            ))),
+        }
+    }
+}
+
+fn string<'a>(value: &'a Value, key: &str) -> Result<Option<&'a str>> {
+    match value.get(key) {
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(Error::parse(format!("'{}' must be a string", key))),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config =
+            Config::parse("session = \"53616c7465\\n\"\nbase_url = \"http://127.0.0.1:8080\"\n")
+                .unwrap();
+        assert_eq!(config.session().unwrap(), "53616c7465");
+        assert_eq!(config.base_url, "http://127.0.0.1:8080");
+
+        let config = Config::parse("").unwrap();
+        assert_eq!(config, Config::default());
+        assert!(config.session().is_err());
+
+        assert_eq!(
+            Config::parse("session = 1\n").unwrap_err().to_string(),
+            "'session' must be a string"
+        );
+    }
+}
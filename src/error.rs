@@ -0,0 +1,252 @@
+use crate::runner::format_duration;
+use crate::solver::Part;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+use std::time::Duration;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    Io(Option<String>, io::Error),
+    Parse(String),
+    Solve(String),
+    Usage(String),
+    Http(String),
+    Panic(String),
+    Cancelled,
+    Timeout(Duration),
+}
+
+#[derive(Debug)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub part: Option<Part>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl Error {
+    fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            year: None,
+            day: None,
+            part: None,
+            line: None,
+            column: None,
+        }
+    }
+
+    pub fn io(path: &str, error: io::Error) -> Error {
+        Error::new(ErrorKind::Io(Some(path.to_string()), error))
+    }
+
+    pub fn parse(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Parse(message.into()))
+    }
+
+    pub fn parse_at(line: usize, column: usize, message: impl Into<String>) -> Error {
+        let mut error = Error::parse(message);
+        error.line = Some(line);
+        error.column = Some(column);
+        error
+    }
+
+    // `token` should be a slice of `line` so the column can be derived from it
+    pub fn at_token(
+        line_number: usize,
+        line: &str,
+        token: &str,
+        message: impl Into<String>,
+    ) -> Error {
+        Error::parse_at(line_number, column_of(line, token), message)
+    }
+
+    pub fn solve(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Solve(message.into()))
+    }
+
+    pub fn usage(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Usage(message.into()))
+    }
+
+    pub fn http(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Http(message.into()))
+    }
+
+    pub fn panic(message: impl Into<String>) -> Error {
+        Error::new(ErrorKind::Panic(message.into()))
+    }
+
+    pub fn cancelled() -> Error {
+        Error::new(ErrorKind::Cancelled)
+    }
+
+    pub fn timeout(timeout: Duration) -> Error {
+        Error::new(ErrorKind::Timeout(timeout))
+    }
+
+    pub fn with_day(mut self, year: u16, day: u8) -> Error {
+        self.year = Some(year);
+        self.day = Some(day);
+        self
+    }
+
+    pub fn with_part(mut self, part: Part) -> Error {
+        self.part = Some(part);
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut location = Vec::new();
+        if let (Some(year), Some(day)) = (self.year, self.day) {
+            location.push(format!("{} day {:02}", year, day));
+        }
+        if let Some(part) = self.part {
+            location.push(format!("part {}", part));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {}", line));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("column {}", column));
+        }
+
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+
+        match &self.kind {
+            ErrorKind::Io(Some(path), error) => write!(f, "unable to read {}: {}", path, error),
+            ErrorKind::Io(None, error) => write!(f, "unable to read input: {}", error),
+            ErrorKind::Parse(message) => write!(f, "{}", message),
+            ErrorKind::Solve(message) => write!(f, "{}", message),
+            ErrorKind::Usage(message) => write!(f, "{}", message),
+            ErrorKind::Http(message) => write!(f, "{}", message),
+            ErrorKind::Panic(message) => write!(f, "panicked: {}", message),
+            ErrorKind::Cancelled => write!(f, "cancelled"),
+            ErrorKind::Timeout(timeout) => {
+                write!(f, "timed out after {}", format_duration(*timeout))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::new(ErrorKind::Io(None, error))
+    }
+}
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Error {
+        let message = error.to_string();
+        match error.line_col() {
+            Some((line, column)) => Error::parse_at(line + 1, column + 1, message),
+            None => Error::parse(message),
+        }
+    }
+}
+
+pub fn column_of(line: &str, token: &str) -> usize {
+    let line_start = line.as_ptr() as usize;
+    let token_start = token.as_ptr() as usize;
+    let offset =
+        if token_start >= line_start && token_start + token.len() <= line_start + line.len() {
+            token_start - line_start
+        } else {
+            line.find(token).unwrap_or(0)
+        };
+
+    line[..offset].chars().count() + 1
+}
+
+pub fn parse_token<T: FromStr>(line_number: usize, line: &str, token: &str) -> Result<T> {
+    token.parse::<T>().map_err(|_| {
+        Error::at_token(
+            line_number,
+            line,
+            token,
+            format!("invalid value '{}'", token),
+        )
+    })
+}
+
+pub fn field<'a>(
+    line_number: usize,
+    line: &str,
+    fields: &[&'a str],
+    index: usize,
+) -> Result<&'a str> {
+    fields.get(index).copied().ok_or_else(|| {
+        Error::parse_at(
+            line_number,
+            line.chars().count() + 1,
+            format!("expected at least {} fields", index + 1),
+        )
+    })
+}
+
+pub fn parse_field<T: FromStr>(
+    line_number: usize,
+    line: &str,
+    fields: &[&str],
+    index: usize,
+) -> Result<T> {
+    parse_token(line_number, line, field(line_number, line, fields, index)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::*;
+
+    #[test]
+    fn test_column_of() {
+        let line = "1-3 a: abcde";
+        let split: Vec<&str> = line.split(' ').collect();
+
+        assert_eq!(column_of(line, split[0]), 1);
+        assert_eq!(column_of(line, split[1]), 5);
+        assert_eq!(column_of(line, split[2]), 8);
+        assert_eq!(column_of(line, &String::from("abc")), 8);
+    }
+
+    #[test]
+    fn test_parse_token() {
+        let line = "2x3xq";
+        let split: Vec<&str> = line.split('x').collect();
+
+        assert_eq!(parse_token::<u32>(1, line, split[1]).unwrap(), 3);
+
+        let error = parse_token::<u32>(4, line, split[2]).unwrap_err();
+        assert_eq!(error.line, Some(4));
+        assert_eq!(error.column, Some(5));
+    }
+
+    #[test]
+    fn test_error_display() {
+        let error = Error::parse_at(3, 7, "invalid value 'x'")
+            .with_day(2020, 4)
+            .with_part(Part::One);
+
+        assert_eq!(
+            error.to_string(),
+            "2020 day 04, part 1, line 3, column 7: invalid value 'x'"
+        );
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::{Error, Result};
+use crate::inputs::Expected;
+use crate::registry::Entry;
+use crate::runner;
+use crate::solver;
+use crate::solver::Part;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const HEADER_END: &str = "---";
+
+// A puzzle example kept in `examples/<year>/dayNN/<name>.txt`. Its answers come
+// from a header of `part1 = "..."` lines ended by `---` at the top of the file,
+// or from `<name>.toml` next to it when the file has no header.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+    pub name: String,
+    pub path: String,
+    pub input: String,
+    pub expected: Expected,
+}
+
+impl Example {
+    pub fn load(path: &Path) -> Result<Example> {
+        let display = path.display().to_string();
+        let contents = solver::read_input(&display)?;
+        let (expected, input) = match split_header(&contents)? {
+            Some((expected, input)) => (expected, input.to_string()),
+            None => {
+                let sidecar = path.with_extension("toml");
+                (Expected::load(&sidecar.display().to_string())?, contents)
+            }
+        };
+        Ok(Example {
+            name: path
+                .file_stem()
+                .map_or(String::new(), |s| s.to_string_lossy().to_string()),
+            path: display,
+            input,
+            expected,
+        })
+    }
+}
+
+// Splits the answers header from the input. Only a file whose first line sets a
+// part has a header, so inputs are otherwise taken as they are.
+pub fn split_header(contents: &str) -> Result<Option<(Expected, &str)>> {
+    let first = contents.lines().next().unwrap_or("");
+    if !(first.starts_with("part1") || first.starts_with("part2")) {
+        return Ok(None);
+    }
+
+    let mut offset = 0;
+    for line in contents.split_inclusive('\n') {
+        if line.trim_end() == HEADER_END {
+            let expected = Expected::parse(&contents[..offset])?;
+            return Ok(Some((expected, &contents[offset + line.len()..])));
+        }
+        offset += line.len();
+    }
+    Err(Error::parse(format!(
+        "expected '{}' after the answers header",
+        HEADER_END
+    )))
+}
+
+// Every example of a day sorted by name, none when it has no examples directory
+pub fn load_examples(year: u16, day: u8) -> Result<Vec<Example>> {
+    let dir = solver::get_examples_dir_path(&year, &day);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::io(&dir, e)),
+    };
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| Error::io(&dir, e))?.path();
+        if path.extension().is_some_and(|e| e == "txt") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths.iter().map(|path| Example::load(path)).collect()
+}
+
+// Solves the parts of `example` that have an answer, returning a description of
+// every part that failed or gave a different answer
+pub fn check(entry: &Entry, example: &Example) -> Vec<String> {
+    let parts: Vec<Part> = [Part::One, Part::Two]
+        .iter()
+        .copied()
+        .filter(|part| example.expected.get(*part).is_some())
+        .collect();
+    if parts.is_empty() {
+        return vec![format!("{}: no expected answers", example.path)];
+    }
+
+    let solver = (entry.solver)();
+    let result = match runner::run(solver, entry.year, entry.day, &example.input, &parts, None) {
+        Ok(result) => result,
+        Err(error) => return vec![format!("{}: {}", example.path, error)],
+    };
+
+    let mut failures = Vec::new();
+    for part in result.parts {
+        let expected = example.expected.get(part.part).unwrap_or_default();
+        match part.answer {
+            Ok(answer) if answer == expected => {}
+            Ok(answer) => failures.push(format!(
+                "{}: part {} gave {} (expected {})",
+                example.path, part.part, answer, expected
+            )),
+            Err(error) => failures.push(format!("{}: {}", example.path, error)),
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::examples::*;
+    use crate::registry;
+
+    // Runs every example of every implemented day, so adding an example only
+    // takes a file
+    #[test]
+    fn test_examples() {
+        let mut checked = 0;
+        let mut failures = Vec::new();
+        for entry in registry::entries() {
+            for example in load_examples(entry.year, entry.day).unwrap() {
+                checked += 1;
+                failures.extend(check(entry, &example));
+            }
+        }
+
+        assert!(
+            checked > 0 || registry::entries().is_empty(),
+            "no examples found"
+        );
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_split_header() {
+        let (expected, input) = split_header("part1 = \"72\"\n---\n123 -> x\n")
+            .unwrap()
+            .unwrap();
+        assert_eq!(expected.get(Part::One), Some("72"));
+        assert_eq!(expected.get(Part::Two), None);
+        assert_eq!(input, "123 -> x\n");
+
+        assert_eq!(split_header("#..#\n---\n").unwrap(), None);
+        assert_eq!(
+            split_header("part1 = \"72\"\n123 -> x\n")
+                .unwrap_err()
+                .to_string(),
+            "expected '---' after the answers header"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::client::Client;
+use crate::config::{Config, CONFIG_FILE_PATH};
+use crate::error::{Error, Result};
+use crate::solver;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached,
+    Downloaded(usize),
+}
+
+fn is_cached(path: &str) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.len() > 0)
+}
+
+// Downloads the input to `path` unless it is already there, so the server is
+// only ever asked once for each day. An empty file, like the one `new` creates,
+// does not count as a cached input.
+pub fn fetch_input(client: &Client, year: u16, day: u8, path: &str) -> Result<Fetched> {
+    if is_cached(path) {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = client
+        .get_input(year, day)
+        .map_err(|e| e.with_day(year, day))?;
+    if let Some(dir) = Path::new(path).parent() {
+        fs::create_dir_all(dir).map_err(|e| Error::io(&dir.display().to_string(), e))?;
+    }
+    // Write to a temporary file first so an interrupted download is not cached
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, &input).map_err(|e| Error::io(&tmp_path, e))?;
+    fs::rename(&tmp_path, path).map_err(|e| Error::io(path, e))?;
+
+    Ok(Fetched::Downloaded(input.len()))
+}
+
+// Fetches the default input of a day with the configured session
+pub fn fetch_day(year: u16, day: u8) -> Result<()> {
+    let path = solver::get_input_file_path(&year, &day);
+    // A cached input needs neither the config file nor a session token
+    if is_cached(&path) {
+        println!("{} is already cached", path);
+        return Ok(());
+    }
+
+    let client = Client::new(&Config::load(CONFIG_FILE_PATH)?)?;
+    match fetch_input(&client, year, day, &path)? {
+        Fetched::Cached => println!("{} is already cached", path),
+        Fetched::Downloaded(bytes) => println!("Downloaded {} ({} bytes)", path, bytes),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::tests::{config, serve_once};
+    use crate::fetch::*;
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = std::env::temp_dir().join("aoc-fetch-test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("2020").join("day01.txt");
+        let path = path.to_str().unwrap();
+
+        let (base_url, server) = serve_once(200, "1721\n979\n");
+        let client = Client::new(&config(&base_url)).unwrap();
+        assert_eq!(
+            fetch_input(&client, 2020, 1, path).unwrap(),
+            Fetched::Downloaded(9)
+        );
+        server.join().unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "1721\n979\n");
+
+        // The stand-in server is gone, so a second download would fail
+        assert_eq!(
+            fetch_input(&client, 2020, 1, path).unwrap(),
+            Fetched::Cached
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_input_over_empty_file() {
+        let dir = std::env::temp_dir().join("aoc-fetch-empty-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("day01.txt");
+        let path = path.to_str().unwrap();
+        fs::write(path, "").unwrap();
+
+        let (base_url, server) = serve_once(200, "1721\n");
+        let client = Client::new(&config(&base_url)).unwrap();
+        assert_eq!(
+            fetch_input(&client, 2016, 1, path).unwrap(),
+            Fetched::Downloaded(5)
+        );
+        server.join().unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "1721\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
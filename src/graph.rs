@@ -0,0 +1,318 @@
+use crate::error::{self, Error};
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Whether a tour returns to the node it started from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tour {
+    Path,
+    Cycle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Shortest,
+    Longest,
+}
+
+// The most nodes `held_karp` accepts, where its table takes hundreds of MB
+pub const HELD_KARP_MAX_NODES: usize = 20;
+
+// A directed graph with weighted edges. Nodes are interned, so every distinct
+// node gets an index in the order it was added and the algorithms work on
+// those indexes.
+#[derive(Debug, Clone)]
+pub struct Graph<N, W> {
+    nodes: Vec<N>,
+    indexes: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, W)>>,
+}
+
+impl<N, W> Default for Graph<N, W> {
+    fn default() -> Graph<N, W> {
+        Graph {
+            nodes: Vec::new(),
+            indexes: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+}
+
+impl<N, W> Graph<N, W>
+where
+    N: Clone + Eq + Hash,
+{
+    pub fn new() -> Graph<N, W> {
+        Graph::default()
+    }
+
+    // Returns the index of `node`, adding it when it is new
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&index) = self.indexes.get(&node) {
+            return index;
+        }
+
+        let index = self.nodes.len();
+        self.indexes.insert(node.clone(), index);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        index
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: W) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn index<Q>(&self, node: &Q) -> Option<usize>
+    where
+        N: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.indexes.get(node).copied()
+    }
+
+    pub fn node(&self, index: usize) -> &N {
+        &self.nodes[index]
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // The nodes `index` has an edge to, with the weights of those edges
+    pub fn edges(&self, index: usize) -> &[(usize, W)] {
+        &self.edges[index]
+    }
+
+    pub fn weight(&self, from: usize, to: usize) -> Option<&W> {
+        self.edges[from]
+            .iter()
+            .find(|(next, _)| *next == to)
+            .map(|(_, weight)| weight)
+    }
+
+    // The same graph with every edge pointing the other way
+    pub fn reversed(&self) -> Graph<N, W>
+    where
+        W: Clone,
+    {
+        let mut edges = vec![Vec::new(); self.len()];
+        for (from, next) in self.edges.iter().enumerate() {
+            for (to, weight) in next {
+                edges[*to].push((from, weight.clone()));
+            }
+        }
+
+        Graph {
+            nodes: self.nodes.clone(),
+            indexes: self.indexes.clone(),
+            edges,
+        }
+    }
+
+    // Computes a value for `start` from the values of the nodes it has edges
+    // to, which `f` is given with the weights of those edges. Every reachable
+    // node is computed once. Returns a node on a cycle when one is reachable.
+    pub fn fold<T, F>(&self, start: usize, mut f: F) -> Result<T, usize>
+    where
+        F: FnMut(usize, Vec<(&W, &T)>) -> T,
+    {
+        let mut values: Vec<Option<T>> = (0..self.len()).map(|_| None).collect();
+        let mut visiting = vec![false; self.len()];
+        self.fold_node(start, &mut f, &mut values, &mut visiting)?;
+        Ok(values[start].take().unwrap())
+    }
+
+    fn fold_node<T, F>(
+        &self,
+        node: usize,
+        f: &mut F,
+        values: &mut [Option<T>],
+        visiting: &mut [bool],
+    ) -> Result<(), usize>
+    where
+        F: FnMut(usize, Vec<(&W, &T)>) -> T,
+    {
+        if values[node].is_some() {
+            return Ok(());
+        }
+        if visiting[node] {
+            return Err(node);
+        }
+
+        visiting[node] = true;
+        for (next, _) in &self.edges[node] {
+            self.fold_node(*next, f, values, visiting)?;
+        }
+        visiting[node] = false;
+
+        let inputs = self.edges[node]
+            .iter()
+            .map(|(next, weight)| (weight, values[*next].as_ref().unwrap()))
+            .collect();
+        values[node] = Some(f(node, inputs));
+        Ok(())
+    }
+
+    // The number of edges on the shortest path from `start` to every node,
+    // `None` for nodes that cannot be reached
+    pub fn bfs(&self, start: usize) -> Vec<Option<usize>> {
+        let mut distances = vec![None; self.len()];
+        distances[start] = Some(0);
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node].unwrap() + 1;
+            for (next, _) in &self.edges[node] {
+                if distances[*next].is_none() {
+                    distances[*next] = Some(distance);
+                    queue.push_back(*next);
+                }
+            }
+        }
+        distances
+    }
+
+    // The total weight of the best tour visiting every node exactly once, by
+    // Held-Karp dynamic programming over subsets. That takes 2^n * n^2 steps
+    // and 2^n * n weights of memory, so graphs of more than
+    // HELD_KARP_MAX_NODES nodes are an error. `None` when there is no tour
+    // along the edges of the graph.
+    pub fn held_karp(&self, tour: Tour, goal: Goal) -> error::Result<Option<W>>
+    where
+        W: Copy + Ord + Add<Output = W> + Default,
+    {
+        let n = self.len();
+        if n == 0 {
+            return Ok(None);
+        }
+        if n > HELD_KARP_MAX_NODES {
+            return Err(Error::solve(format!(
+                "{} nodes are too many to find the best tour, the limit is {}",
+                n, HELD_KARP_MAX_NODES
+            )));
+        }
+
+        let better = |a: W, b: W| match goal {
+            Goal::Shortest => a.min(b),
+            Goal::Longest => a.max(b),
+        };
+        let weights: Vec<Vec<Option<W>>> = (0..n)
+            .map(|from| (0..n).map(|to| self.weight(from, to).copied()).collect())
+            .collect();
+
+        // best[visited][last] is the best weight of visiting the nodes in the
+        // `visited` bit set, ending at `last`. A cycle can start anywhere, so
+        // it starts at node 0.
+        let mut best: Vec<Vec<Option<W>>> = vec![vec![None; n]; 1 << n];
+        match tour {
+            Tour::Path => (0..n).for_each(|i| best[1 << i][i] = Some(W::default())),
+            Tour::Cycle => best[1][0] = Some(W::default()),
+        }
+
+        for visited in 1..1usize << n {
+            for last in 0..n {
+                let weight = match best[visited][last] {
+                    Some(weight) => weight,
+                    None => continue,
+                };
+                for next in (0..n).filter(|next| visited & 1 << next == 0) {
+                    if let Some(edge) = weights[last][next] {
+                        let entry = &mut best[visited | 1 << next][next];
+                        let total = weight + edge;
+                        *entry = Some(entry.map_or(total, |e| better(e, total)));
+                    }
+                }
+            }
+        }
+
+        let all = (1 << n) - 1;
+        Ok((0..n)
+            .filter_map(|last| match tour {
+                Tour::Path => best[all][last],
+                Tour::Cycle if n == 1 => best[all][last],
+                Tour::Cycle => Some(best[all][last]? + weights[last][0]?),
+            })
+            .reduce(better))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::*;
+
+    fn graph(edges: &[(&'static str, &'static str, usize)]) -> Graph<&'static str, usize> {
+        let mut graph = Graph::new();
+        for &(from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    #[test]
+    fn test_fold() {
+        let dag = graph(&[("a", "b", 2), ("a", "c", 3), ("b", "c", 4), ("d", "a", 1)]);
+        assert_eq!(dag.index("c"), Some(2));
+        assert_eq!(dag.len(), 4);
+
+        // The number of paths from a node to "c", each counted with the
+        // product of its weights
+        let mut calls = 0;
+        let paths = dag.fold(dag.index("d").unwrap(), |node, inputs| {
+            calls += 1;
+            if *dag.node(node) == "c" {
+                1
+            } else {
+                inputs.iter().map(|(w, v)| *w * *v).sum::<usize>()
+            }
+        });
+        assert_eq!(paths, Ok(2 * 4 + 3));
+        assert_eq!(calls, 4);
+
+        let cyclic = graph(&[("x", "a", 1), ("a", "b", 1), ("b", "c", 1), ("c", "a", 1)]);
+        assert!([1, 2, 3].contains(&cyclic.fold(0, |_, _| ()).unwrap_err()));
+    }
+
+    #[test]
+    fn test_bfs() {
+        let roads = graph(&[("a", "b", 7), ("a", "c", 2), ("c", "b", 3), ("b", "d", 1)]);
+        let a = roads.index("a").unwrap();
+        assert_eq!(roads.bfs(a), vec![Some(0), Some(1), Some(1), Some(2)]);
+        assert_eq!(roads.reversed().bfs(a), vec![Some(0), None, None, None]);
+    }
+
+    #[test]
+    fn test_held_karp() {
+        let mut cities = Graph::new();
+        for &(from, to, distance) in &[
+            ("London", "Dublin", 464),
+            ("London", "Belfast", 518),
+            ("Dublin", "Belfast", 141),
+        ] {
+            cities.add_edge(from, to, distance);
+            cities.add_edge(to, from, distance);
+        }
+        let best = |graph: &Graph<&str, usize>, tour, goal| graph.held_karp(tour, goal).unwrap();
+        assert_eq!(best(&cities, Tour::Path, Goal::Shortest), Some(605));
+        assert_eq!(best(&cities, Tour::Path, Goal::Longest), Some(982));
+        assert_eq!(best(&cities, Tour::Cycle, Goal::Shortest), Some(1123));
+
+        let one_way = graph(&[("a", "b", 1), ("b", "c", 1)]);
+        assert_eq!(best(&one_way, Tour::Path, Goal::Shortest), Some(2));
+        assert_eq!(best(&one_way, Tour::Cycle, Goal::Shortest), None);
+        assert_eq!(best(&Graph::new(), Tour::Path, Goal::Shortest), None);
+
+        let mut line = Graph::new();
+        for i in 0..HELD_KARP_MAX_NODES {
+            line.add_edge(i, i + 1, 1);
+        }
+        assert!(line.held_karp(Tour::Path, Goal::Shortest).is_err());
+    }
+}
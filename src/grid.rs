@@ -0,0 +1,367 @@
+use crate::error::{Error, Result};
+use std::ops::{Index, IndexMut};
+
+// Offsets of the 4 orthogonal neighbours, clockwise from up
+pub const DIRECTIONS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+// Offsets of all 8 neighbours, clockwise from up
+pub const DIRECTIONS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+// A rectangular map of cells addressed by `(x, y)`, with `x` counting columns
+// from the left and `y` counting rows from the top
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    // Parses one row per line, converting every character with `cell`. A
+    // character it rejects is reported with `expected` as the message.
+    pub fn parse<F>(text: &str, expected: &str, cell: F) -> Result<Grid<T>>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        Grid::parse_lines(
+            text.lines().enumerate().map(|(i, l)| (i + 1, l)),
+            expected,
+            cell,
+        )
+    }
+
+    // Like `parse` for lines taken from a larger input, given with their line numbers
+    pub fn parse_lines<'a, I, F>(lines: I, expected: &str, cell: F) -> Result<Grid<T>>
+    where
+        I: IntoIterator<Item = (usize, &'a str)>,
+        F: Fn(char) -> Option<T>,
+    {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        for (line_number, line) in lines {
+            let row_start = cells.len();
+            for (i, c) in line.chars().enumerate() {
+                let value = cell(c).ok_or_else(|| Error::parse_at(line_number, i + 1, expected))?;
+                cells.push(value);
+            }
+            let row_width = cells.len() - row_start;
+            match width {
+                None if row_width == 0 => {
+                    return Err(Error::parse_at(
+                        line_number,
+                        1,
+                        "expected a row of the grid",
+                    ))
+                }
+                None => width = Some(row_width),
+                Some(width) if width != row_width => {
+                    return Err(Error::parse_at(
+                        line_number,
+                        1,
+                        format!("expected {} columns like the rows above", width),
+                    ))
+                }
+                Some(_) => {}
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            None => Err(Error::parse("expected a grid")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    // The grid repeats in both directions, so every position has a cell
+    pub fn get_wrapping(&self, x: isize, y: isize) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self.cells[y * self.width + x]
+    }
+
+    // The position `(dx, dy)` away from `(x, y)`, if it lies within the grid
+    pub fn offset(&self, x: usize, y: usize, dx: isize, dy: isize) -> Option<(usize, usize)> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        if x < self.width && y < self.height {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_4
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(x, y, dx, dy))
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        DIRECTIONS_8
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(x, y, dx, dy))
+    }
+
+    // Positions passed when moving from `(x, y)` by `(dx, dy)` until leaving the
+    // grid, not including `(x, y)` itself
+    pub fn ray(
+        &self,
+        x: usize,
+        y: usize,
+        dx: isize,
+        dy: isize,
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        std::iter::successors(self.offset(x, y, dx, dy), move |&(x, y)| {
+            self.offset(x, y, dx, dy)
+        })
+    }
+
+    // Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> Vec<T>
+    where
+        T: Clone,
+    {
+        (0..self.height).map(|y| self[(x, y)].clone()).collect()
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    // The `width` by `height` part of the grid whose top left cell is `(x, y)`
+    pub fn subgrid(&self, x: usize, y: usize, width: usize, height: usize) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: (y..y + height)
+                .flat_map(|y| self.row(y)[x..x + width].iter().cloned())
+                .collect(),
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        Grid {
+            width: self.height,
+            height: self.width,
+            cells: (0..self.width)
+                .flat_map(|x| (0..height).rev().map(move |y| (x, y)))
+                .map(|position| self[position].clone())
+                .collect(),
+        }
+    }
+
+    // Mirrors the grid left to right
+    pub fn flip_horizontal(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: (0..self.height)
+                .flat_map(|y| self.row(y).iter().rev().cloned())
+                .collect(),
+        }
+    }
+
+    // Mirrors the grid top to bottom
+    pub fn flip_vertical(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: (0..self.height)
+                .rev()
+                .flat_map(|y| self.row(y).iter().cloned())
+                .collect(),
+        }
+    }
+
+    // One line per row, the reverse of `parse`
+    pub fn render<F>(&self, cell: F) -> String
+    where
+        F: Fn(&T) -> char,
+    {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for y in 0..self.height {
+            text.extend(self.row(y).iter().map(&cell));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside the {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(x, y)
+            .unwrap_or_else(|| panic!("({}, {}) is outside the {}x{} grid", x, y, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::*;
+
+    fn parse(text: &str) -> Grid<char> {
+        Grid::parse(text, "expected '.' or '#'", |c| {
+            if c == '.' || c == '#' {
+                Some(c)
+            } else {
+                None
+            }
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_render() {
+        let grid = parse("#..\n.#.\n");
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(0, 0)], '#');
+        assert_eq!(grid.get(1, 1), Some(&'#'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.render(|c| *c), "#..\n.#.\n");
+
+        let error = Grid::parse("#.\n.x\n", "expected '.' or '#'", |c| {
+            if c == 'x' {
+                None
+            } else {
+                Some(c)
+            }
+        })
+        .unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(2)));
+        assert_eq!(
+            Grid::parse("#.\n#\n", "", Some).unwrap_err().to_string(),
+            "line 2, column 1: expected 2 columns like the rows above"
+        );
+    }
+
+    #[test]
+    fn test_wrapping_and_neighbours() {
+        let grid = parse("#..\n.#.\n..#\n");
+        assert_eq!(*grid.get_wrapping(5, -1), '#');
+        assert_eq!(*grid.get_wrapping(-1, 4), '.');
+
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).count(), 3);
+        assert_eq!(
+            grid.ray(0, 0, 1, 1).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2)]
+        );
+        assert_eq!(grid.ray(0, 0, -1, 0).count(), 0);
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = parse("#..\n##.\n");
+        assert_eq!(grid.rotate_clockwise().render(|c| *c), "##\n#.\n..\n");
+        assert_eq!(grid.flip_horizontal().render(|c| *c), "..#\n.##\n");
+        assert_eq!(grid.flip_vertical().render(|c| *c), "##.\n#..\n");
+        assert_eq!(grid.subgrid(1, 0, 2, 2).render(|c| *c), "..\n#.\n");
+        assert_eq!(grid.column(0), vec!['#', '#']);
+        assert_eq!(
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise(),
+            grid
+        );
+    }
+}
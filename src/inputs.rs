@@ -0,0 +1,224 @@
+use crate::answers::{part_from_key, part_number};
+use crate::error::{Error, Result};
+use crate::solver;
+use crate::solver::Part;
+use std::fs;
+use std::io;
+use std::path::Path;
+use toml::Value;
+
+pub const DEFAULT_INPUT: &str = "default";
+
+// One input for a day: the default `input/<year>/dayNN.txt`, whose answers are
+// kept in the answers file, or a named `input/<year>/dayNN/<name>.txt` with its
+// answers in `<name>.toml` next to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputSet {
+    pub name: String,
+    pub path: String,
+}
+
+impl InputSet {
+    pub fn new(name: &str, path: &str) -> InputSet {
+        InputSet {
+            name: name.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_INPUT
+    }
+
+    pub fn load(&self) -> Result<String> {
+        solver::read_input(&self.path)
+    }
+
+    pub fn answers_path(&self) -> Option<String> {
+        if self.is_default() {
+            return None;
+        }
+        Path::new(&self.path)
+            .with_extension("toml")
+            .to_str()
+            .map(String::from)
+    }
+}
+
+pub fn get_input_dir_path(year: &u16, day: &u8) -> String {
+    format!("input/{}/day{:02}", year, day)
+}
+
+// The default input when it exists, followed by the named inputs sorted by name.
+// A day without any input still gets the default so that reading it reports
+// the missing file.
+pub fn input_sets(year: u16, day: u8) -> Result<Vec<InputSet>> {
+    let default_path = solver::get_input_file_path(&year, &day);
+    let mut sets = Vec::new();
+    if Path::new(&default_path).exists() {
+        sets.push(InputSet::new(DEFAULT_INPUT, &default_path));
+    }
+
+    sets.extend(named_input_sets(&get_input_dir_path(&year, &day))?);
+
+    if sets.is_empty() {
+        sets.push(InputSet::new(DEFAULT_INPUT, &default_path));
+    }
+    Ok(sets)
+}
+
+// The `<name>.txt` inputs in `dir` sorted by name. `default` is the name of the
+// default input, so a named input cannot use it.
+fn named_input_sets(dir: &str) -> Result<Vec<InputSet>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(Error::io(dir, e)),
+    };
+
+    let mut named = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| Error::io(dir, e))?.path();
+        if path.extension().is_none_or(|e| e != "txt") {
+            continue;
+        }
+        if let (Some(name), Some(path)) = (path.file_stem(), path.to_str()) {
+            let name = name.to_string_lossy();
+            if name == DEFAULT_INPUT {
+                return Err(Error::usage(format!(
+                    "{}: '{}' is reserved for the default input, rename the file",
+                    path, DEFAULT_INPUT
+                )));
+            }
+            named.push(InputSet::new(&name, path));
+        }
+    }
+    named.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(named)
+}
+
+// Expected answers of a named input, stored as `part1 = "..."` and `part2 = "..."`
+#[derive(Debug, Default, PartialEq)]
+pub struct Expected {
+    answers: [Option<String>; 2],
+}
+
+impl Expected {
+    // A missing file means no answers are known yet
+    pub fn load(path: &str) -> Result<Expected> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Expected::parse(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Expected::default()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Expected> {
+        let value: Value = contents.parse()?;
+        let table = value
+            .as_table()
+            .ok_or_else(|| Error::parse("expected a table of answers"))?;
+
+        let mut expected = Expected::default();
+        for (key, answer) in table {
+            let part = part_from_key(key)
+                .ok_or_else(|| Error::parse(format!("invalid part '{}'", key)))?;
+            let answer = answer
+                .as_str()
+                .ok_or_else(|| Error::parse(format!("answer for {} must be a string", key)))?;
+            expected.set(part, answer);
+        }
+        Ok(expected)
+    }
+
+    pub fn get(&self, part: Part) -> Option<&str> {
+        self.answers[part_number(part) as usize - 1].as_deref()
+    }
+
+    pub fn set(&mut self, part: Part, answer: &str) {
+        self.answers[part_number(part) as usize - 1] = Some(answer.to_string());
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let mut contents = String::new();
+        for part in &[Part::One, Part::Two] {
+            if let Some(answer) = self.get(*part) {
+                contents.push_str(&format!(
+                    "part{} = {}\n",
+                    part,
+                    Value::String(answer.to_string())
+                ));
+            }
+        }
+        fs::write(path, contents).map_err(|e| Error::io(path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::inputs::*;
+
+    #[test]
+    fn test_input_sets() {
+        let sets = input_sets(2020, 1).unwrap();
+        assert_eq!(sets[0], InputSet::new("default", "input/2020/day01.txt"));
+        assert!(sets[0].is_default());
+        assert_eq!(sets[0].answers_path(), None);
+
+        let named = InputSet::new("alice", "input/2020/day01/alice.txt");
+        assert_eq!(
+            named.answers_path(),
+            Some(String::from("input/2020/day01/alice.toml"))
+        );
+
+        assert_eq!(
+            input_sets(2016, 1).unwrap(),
+            vec![InputSet::new("default", "input/2016/day01.txt")]
+        );
+    }
+
+    #[test]
+    fn test_named_input_sets() {
+        let dir = std::env::temp_dir().join("aoc-named-inputs-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for file in &["bob.txt", "alice.txt", "alice.toml"] {
+            fs::write(dir.join(file), "").unwrap();
+        }
+        let names: Vec<String> = named_input_sets(dir.to_str().unwrap())
+            .unwrap()
+            .into_iter()
+            .map(|set| set.name)
+            .collect();
+        assert_eq!(names, vec!["alice", "bob"]);
+
+        fs::write(dir.join("default.txt"), "").unwrap();
+        let error = named_input_sets(dir.to_str().unwrap()).unwrap_err();
+        assert!(error.to_string().ends_with(
+            "default.txt: 'default' is reserved for the default input, rename the file"
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(named_input_sets(dir.to_str().unwrap()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_expected_round_trip() {
+        let expected = Expected::parse("part2 = \"rcqb,cltx\"\n").unwrap();
+        assert_eq!(expected.get(Part::One), None);
+        assert_eq!(expected.get(Part::Two), Some("rcqb,cltx"));
+
+        let path = std::env::temp_dir().join("aoc-expected-test.toml");
+        let path = path.to_str().unwrap();
+        let mut expected = Expected::default();
+        expected.set(Part::One, "2659");
+        expected.save(path).unwrap();
+        let loaded = Expected::load(path).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(loaded, expected);
+
+        assert_eq!(
+            Expected::parse("part3 = \"1\"\n").unwrap_err().to_string(),
+            "invalid part 'part3'"
+        );
+    }
+}
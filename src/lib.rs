@@ -0,0 +1,83 @@
+// Solvers for every implemented Advent of Code day, along with the tooling the
+// command line uses to fetch inputs, submit answers and time the solvers.
+//
+// Days register themselves with the `registry`, so a day can be solved by
+// year and day number without naming its module:
+//
+//     let (part_one, part_two) = advent_of_code_rust::solve(2020, 1, "1721\n979\n...")?;
+#[cfg(feature = "pcre2")]
+extern crate pcre2;
+
+pub mod answers;
+pub mod automaton;
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod examples;
+pub mod fetch;
+pub mod graph;
+pub mod grid;
+pub mod inputs;
+pub mod math;
+pub mod memory;
+pub mod output;
+pub mod parse;
+#[macro_use]
+pub mod registry;
+pub mod runner;
+pub mod scaffold;
+pub mod solver;
+pub mod submit;
+pub mod watch;
+
+// Every year lists its days in its mod.rs
+#[cfg(feature = "y2015")]
+pub mod y2015;
+#[cfg(feature = "y2020")]
+pub mod y2020;
+
+pub use crate::error::{Error, Result};
+pub use crate::solver::{DynSolver, Part, Solver};
+
+// Parses `input` with the registered solver for the day and solves both parts
+pub fn solve(year: u16, day: u8, input: &str) -> Result<(String, String)> {
+    let entry = registry::find_day(year, day)?;
+    let solver = (entry.solver)();
+    let input = solver.parse(input).map_err(|e| e.with_day(year, day))?;
+
+    let part_one = solver
+        .part_one(&input)
+        .map_err(|e| e.with_day(year, day).with_part(Part::One))?;
+    let part_two = solver
+        .part_two(&input)
+        .map_err(|e| e.with_day(year, day).with_part(Part::Two))?;
+    Ok((part_one, part_two))
+}
+
+#[cfg(all(test, feature = "y2015", feature = "y2020"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve(2020, 1, "1721\n979\n366\n299\n675\n1456\n").unwrap(),
+            (String::from("514579"), String::from("241861950"))
+        );
+        assert_eq!(
+            solve(2015, 1, "()())\n").unwrap(),
+            (String::from("-1"), String::from("5"))
+        );
+        assert_eq!(
+            solve(2016, 1, "").unwrap_err().to_string(),
+            "2016 day 01 is not implemented"
+        );
+        assert_eq!(
+            solve(2020, 1, "1721\nx\n").unwrap_err().to_string(),
+            "2020 day 01, line 2, column 1: invalid value 'x'"
+        );
+    }
+}
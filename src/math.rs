@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+// Returns `(g, x, y)` with `g` the greatest common divisor of `a` and `b` and
+// `a * x + b * y == g`
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a.abs(), a.signum(), 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a.rem_euclid(b));
+        (g, y, x - a.div_euclid(b) * y)
+    }
+}
+
+// The `x` in `0..modulus` with `a * x` congruent to 1, which only exists when
+// `a` and `modulus` are coprime and `modulus` is positive
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    if modulus <= 0 {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g == 1 {
+        Some(x.rem_euclid(modulus))
+    } else {
+        None
+    }
+}
+
+// `base` to the power of `exponent` by repeated squaring, `None` for a modulus
+// of zero
+pub fn mod_pow(base: u64, exponent: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let modulus = modulus as u128;
+    let mut result = 1 % modulus;
+    let mut base = base as u128 % modulus;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    Some(result as u64)
+}
+
+// `a * b % modulus` for `a` and `b` in `0..modulus`, doubling and adding when
+// the product does not fit
+fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+
+    let (mut a, mut b, mut result) = (a, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, modulus);
+        }
+        a = add_mod(a, a, modulus);
+        b >>= 1;
+    }
+    result
+}
+
+// `(a + b) % modulus` for `a` and `b` in `0..modulus`, without the sum ever
+// going past `modulus`
+fn add_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    if a >= modulus - b {
+        a - (modulus - b)
+    } else {
+        a + b
+    }
+}
+
+// Solves the system of `x ≡ residue (mod modulus)` congruences, which may have
+// moduli with common factors. Returns the smallest non-negative `x` with the
+// least common multiple of the moduli, or `None` when the congruences conflict,
+// a modulus is not positive or that multiple does not fit in an `i128`.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut lcm = 1;
+    for &(residue, modulus) in congruences {
+        if modulus <= 0 {
+            return None;
+        }
+        let (g, p, _) = extended_gcd(lcm, modulus);
+        let difference = residue.rem_euclid(modulus) - x;
+        if difference % g != 0 {
+            return None;
+        }
+
+        // x + lcm * k ≡ residue (mod modulus), where p is the inverse of
+        // lcm / g modulo modulus / g
+        let step = modulus / g;
+        let k = mul_mod((difference / g).rem_euclid(step), p.rem_euclid(step), step);
+        let next_lcm = lcm.checked_mul(step)?;
+        x = (x + lcm * k).rem_euclid(next_lcm);
+        lcm = next_lcm;
+    }
+
+    Some((x, lcm))
+}
+
+// The smallest `x` with `base` to the power of `x` congruent to `target`, found
+// with baby-step giant-step in about `sqrt(modulus)` steps. `base` has to be
+// coprime with `modulus`, which has to be positive.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    let inverse = mod_inverse(base as i128, modulus as i128)? as u64;
+    let steps = (modulus as f64).sqrt().ceil() as u64;
+
+    // Baby steps: base^j for every j below `steps`, keeping the smallest j
+    let mut baby_steps = HashMap::new();
+    let mut value = 1 % modulus;
+    for j in 0..steps {
+        baby_steps.entry(value).or_insert(j);
+        value = (value as u128 * base as u128 % modulus as u128) as u64;
+    }
+
+    // Giant steps: target * base^-(i * steps) for every i
+    let factor = mod_pow(inverse, steps, modulus)?;
+    let mut gamma = target % modulus;
+    for i in 0..steps {
+        if let Some(j) = baby_steps.get(&gamma) {
+            return Some(i * steps + j);
+        }
+        gamma = (gamma as u128 * factor as u128 % modulus as u128) as u64;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::*;
+
+    #[test]
+    fn test_inverse_and_pow() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(4, 10), None);
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -11), None);
+
+        assert_eq!(mod_pow(7, 8, 20201227), Some(5764801));
+        assert_eq!(mod_pow(2, 0, 1), Some(0));
+        assert_eq!(
+            mod_pow(u64::MAX - 1, u64::MAX, u64::MAX),
+            Some(u64::MAX - 1)
+        );
+        assert_eq!(mod_pow(2, 3, 0), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(-1, 10)]), Some((9, 10)));
+
+        let big = 1_000_000_000_000_000_003;
+        assert_eq!(
+            crt(&[(1, big), (2, big - 2)]).map(|(_, m)| m),
+            Some(big * (big - 2))
+        );
+        assert_eq!(crt(&[(1, big), (2, big - 2), (3, big - 4)]), None);
+
+        // Coefficients near the modulus overflow when multiplied directly
+        let huge = (i128::MAX / 3) | 1;
+        assert_eq!(
+            crt(&[(1, 2), (huge - 1, huge)]),
+            Some((2 * huge - 1, 2 * huge))
+        );
+
+        // Doubling near a modulus above half of i128::MAX
+        let top = i128::MAX - 1;
+        assert_eq!(mul_mod(top - 1, top - 1, top), 1);
+        assert_eq!(mul_mod(top - 1, 2, top), top - 2);
+
+        assert_eq!(crt(&[(1, 0)]), None);
+        assert_eq!(crt(&[(1, 3), (2, -5)]), None);
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(2, 1, 11), Some(0));
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(discrete_log(2, 3, 4), None);
+        assert_eq!(discrete_log(2, 3, 0), None);
+    }
+}
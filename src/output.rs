@@ -0,0 +1,224 @@
+use crate::error::{Error, Result};
+use crate::runner::{DayReport, Status};
+use crate::solver::Part;
+use std::io;
+use std::io::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+// One part of one day. A day whose input could not be loaded or parsed has a
+// single record without a part.
+#[derive(Debug, PartialEq)]
+pub struct Record {
+    pub year: u16,
+    pub day: u8,
+    pub input: String,
+    pub part: Option<Part>,
+    pub answer: Option<String>,
+    pub duration: Option<Duration>,
+    pub cached: bool,
+    pub status: Status,
+    pub error: Option<String>,
+}
+
+pub fn records(reports: &[&DayReport]) -> Vec<Record> {
+    let mut records = Vec::new();
+    for report in reports {
+        match &report.result {
+            Ok(result) => {
+                for part in &result.parts {
+                    records.push(Record {
+                        year: report.year,
+                        day: report.day,
+                        input: report.input.clone(),
+                        part: Some(part.part),
+                        answer: part.answer.as_ref().ok().cloned(),
+                        duration: Some(part.duration),
+                        cached: part.cached,
+                        status: Status::of(&part.answer),
+                        error: part.answer.as_ref().err().map(|e| e.to_string()),
+                    });
+                }
+            }
+            Err(error) => records.push(Record {
+                year: report.year,
+                day: report.day,
+                input: report.input.clone(),
+                part: None,
+                answer: None,
+                duration: None,
+                cached: false,
+                status: Status::of(&report.result),
+                error: Some(error.to_string()),
+            }),
+        }
+    }
+    records
+}
+
+// Writes a JSON array with one record per line so runs can be diffed line by line
+pub fn write_json(out: &mut dyn Write, records: &[Record]) -> io::Result<()> {
+    writeln!(out, "[")?;
+    for (i, record) in records.iter().enumerate() {
+        writeln!(
+            out,
+            "  {{\"year\": {}, \"day\": {}, \"input\": {}, \"part\": {}, \"answer\": {}, \"duration_ns\": {}, \"cached\": {}, \"status\": {}, \"error\": {}}}{}",
+            record.year,
+            record.day,
+            json_string(&record.input),
+            record.part.map_or(String::from("null"), |p| p.to_string()),
+            record.answer.as_deref().map_or(String::from("null"), json_string),
+            record
+                .duration
+                .map_or(String::from("null"), |d| d.as_nanos().to_string()),
+            record.cached,
+            json_string(&record.status.to_string()),
+            record.error.as_deref().map_or(String::from("null"), json_string),
+            if i + 1 < records.len() { "," } else { "" }
+        )?;
+    }
+    writeln!(out, "]")
+}
+
+pub fn write_csv(out: &mut dyn Write, records: &[Record]) -> io::Result<()> {
+    writeln!(
+        out,
+        "year,day,input,part,answer,duration_ns,cached,status,error"
+    )?;
+    for record in records {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            record.year,
+            record.day,
+            csv_field(&record.input),
+            record.part.map_or(String::new(), |p| p.to_string()),
+            record.answer.as_deref().map_or(String::new(), csv_field),
+            record
+                .duration
+                .map_or(String::new(), |d| d.as_nanos().to_string()),
+            record.cached,
+            record.status,
+            record.error.as_deref().map_or(String::new(), csv_field)
+        )?;
+    }
+    Ok(())
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+// Quotes a field only when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Prints the records of `reports` to standard output, which the text format
+// leaves to the caller
+pub fn print_records(format: Format, reports: &[&DayReport]) -> Result<()> {
+    let records = records(reports);
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    match format {
+        Format::Json => write_json(&mut out, &records),
+        Format::Csv => write_csv(&mut out, &records),
+        Format::Text => Ok(()),
+    }
+    .map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::output::*;
+    use crate::runner::{DayResult, PartResult};
+
+    fn reports() -> Vec<DayReport> {
+        vec![
+            DayReport {
+                year: 2020,
+                day: 21,
+                input: String::from("default"),
+                result: Ok(DayResult {
+                    parse: Duration::from_micros(5),
+                    parse_memory: None,
+                    parts: vec![
+                        PartResult {
+                            part: Part::One,
+                            answer: Ok(String::from("2659")),
+                            duration: Duration::from_nanos(1500),
+                            memory: None,
+                            cached: false,
+                        },
+                        PartResult {
+                            part: Part::Two,
+                            answer: Ok(String::from("rcqb,cltx")),
+                            duration: Duration::ZERO,
+                            memory: None,
+                            cached: true,
+                        },
+                    ],
+                }),
+            },
+            DayReport {
+                year: 2020,
+                day: 22,
+                input: String::from("alice"),
+                result: Err(Error::parse_at(1, 1, "expected \"Player 1:\"").with_day(2020, 22)),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_write_json() {
+        let mut out = Vec::new();
+        write_json(&mut out, &records(&reports().iter().collect::<Vec<_>>())).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[\n  \
+             {\"year\": 2020, \"day\": 21, \"input\": \"default\", \"part\": 1, \"answer\": \"2659\", \"duration_ns\": 1500, \"cached\": false, \"status\": \"OK\", \"error\": null},\n  \
+             {\"year\": 2020, \"day\": 21, \"input\": \"default\", \"part\": 2, \"answer\": \"rcqb,cltx\", \"duration_ns\": 0, \"cached\": true, \"status\": \"OK\", \"error\": null},\n  \
+             {\"year\": 2020, \"day\": 22, \"input\": \"alice\", \"part\": null, \"answer\": null, \"duration_ns\": null, \"cached\": false, \"status\": \"ERROR\", \"error\": \"2020 day 22, line 1, column 1: expected \\\"Player 1:\\\"\"}\n\
+             ]\n"
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let mut out = Vec::new();
+        write_csv(&mut out, &records(&reports().iter().collect::<Vec<_>>())).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "year,day,input,part,answer,duration_ns,cached,status,error\n\
+             2020,21,default,1,2659,1500,false,OK,\n\
+             2020,21,default,2,\"rcqb,cltx\",0,true,OK,\n\
+             2020,22,alice,,,,false,ERROR,\"2020 day 22, line 1, column 1: expected \"\"Player 1:\"\"\"\n"
+        );
+    }
+}
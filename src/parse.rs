@@ -0,0 +1,196 @@
+use crate::error::{parse_token, Error, Result};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[cfg(feature = "regex")]
+use regex::{Captures, Regex};
+
+// A group of lines not separated by a blank line, each with its line number
+pub type Paragraph<'a> = Vec<(usize, &'a str)>;
+
+// Splits `text` into the groups of lines between blank lines, skipping any
+// number of blank lines around them
+pub fn paragraphs(text: &str) -> Vec<Paragraph<'_>> {
+    let mut paragraphs = Vec::new();
+    let mut paragraph = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph);
+                paragraph = Vec::new();
+            }
+        } else {
+            paragraph.push((i + 1, line));
+        }
+    }
+
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    paragraphs
+}
+
+// One number on every line
+pub fn numbers<T: FromStr>(text: &str) -> Result<Vec<T>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| parse_token(i + 1, line, line))
+        .collect()
+}
+
+// Numbers on one line between `separator`s, like `7,13,x` without the `x`
+pub fn number_list<T: FromStr>(line_number: usize, line: &str, separator: char) -> Result<Vec<T>> {
+    line.split(separator)
+        .map(|n| parse_token(line_number, line, n))
+        .collect()
+}
+
+// The `key<separator>value` pairs separated by whitespace on any of `lines`. A
+// key given twice keeps its last value.
+pub fn record(lines: &[(usize, &str)], separator: char) -> Result<HashMap<String, String>> {
+    let mut record = HashMap::new();
+    for &(line_number, line) in lines {
+        for pair in line.split_whitespace() {
+            match pair.split_once(separator) {
+                Some((key, value)) if !value.contains(separator) => {
+                    record.insert(key.to_string(), value.to_string());
+                }
+                _ => {
+                    return Err(Error::at_token(
+                        line_number,
+                        line,
+                        pair,
+                        format!("expected key{}value, found '{}'", separator, pair),
+                    ))
+                }
+            }
+        }
+    }
+    Ok(record)
+}
+
+// The groups a regex captured on one line of the input, which can be parsed
+// with errors pointing at the group in the line
+#[cfg(feature = "regex")]
+pub struct Captured<'t> {
+    line_number: usize,
+    line: &'t str,
+    captures: Captures<'t>,
+}
+
+#[cfg(feature = "regex")]
+impl<'t> Captured<'t> {
+    pub fn str(&self, group: usize) -> Result<&'t str> {
+        self.captures.get(group).map(|m| m.as_str()).ok_or_else(|| {
+            Error::parse_at(
+                self.line_number,
+                1,
+                format!("expected group {} to match", group),
+            )
+        })
+    }
+
+    pub fn get<T: FromStr>(&self, group: usize) -> Result<T> {
+        parse_token(self.line_number, self.line, self.str(group)?)
+    }
+}
+
+// Matches the whole of `line` with `regex`, reporting `expected` when it does
+// not match
+#[cfg(feature = "regex")]
+pub fn captures<'t>(
+    regex: &Regex,
+    line_number: usize,
+    line: &'t str,
+    expected: &str,
+) -> Result<Captured<'t>> {
+    regex
+        .captures(line)
+        .filter(|c| {
+            c.get(0)
+                .is_some_and(|m| m.start() == 0 && m.end() == line.len())
+        })
+        .map(|captures| Captured {
+            line_number,
+            line,
+            captures,
+        })
+        .ok_or_else(|| Error::parse_at(line_number, 1, expected))
+}
+
+// Builds a value from every line of `text` with what `regex` captured on it
+#[cfg(feature = "regex")]
+pub fn lines_matching<T, F>(regex: &Regex, text: &str, expected: &str, f: F) -> Result<Vec<T>>
+where
+    F: Fn(&Captured) -> Result<T>,
+{
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| f(&captures(regex, i + 1, line, expected)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::*;
+
+    #[test]
+    fn test_paragraphs_and_numbers() {
+        let groups = paragraphs("\nabc\n\na\nb\n\n\nc\n");
+        assert_eq!(
+            groups,
+            vec![vec![(2, "abc")], vec![(4, "a"), (5, "b")], vec![(8, "c")]]
+        );
+        assert!(paragraphs("").is_empty());
+
+        assert_eq!(numbers::<u32>("1721\n979\n").unwrap(), vec![1721, 979]);
+        let error = numbers::<u32>("1721\n97x\n").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+
+        assert_eq!(number_list::<u8>(1, "0,3,6", ',').unwrap(), vec![0, 3, 6]);
+        let error = number_list::<u8>(3, "0,3,x", ',').unwrap_err();
+        assert_eq!((error.line, error.column), (Some(3), Some(5)));
+    }
+
+    #[test]
+    fn test_record() {
+        let lines = [(4, "ecl:gry pid:860033327"), (5, "hcl:#fffffd")];
+        let passport = record(&lines, ':').unwrap();
+        assert_eq!(passport.len(), 3);
+        assert_eq!(passport["hcl"], "#fffffd");
+
+        let error = record(&[(2, "ecl:gry byr")], ':').unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 9: expected key:value, found 'byr'"
+        );
+        assert!(record(&[(1, "a:b:c")], ':').is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "regex")]
+    fn test_captures() {
+        let regex = Regex::new(r"mem\[(\d+)\] = (\d+)").unwrap();
+        let captured = captures(&regex, 1, "mem[8] = 11", "expected a write").unwrap();
+        assert_eq!(captured.get::<u64>(1).unwrap(), 8);
+        assert_eq!(captured.str(2).unwrap(), "11");
+        let captured = captures(&regex, 3, "mem[300] = 11", "expected a write").unwrap();
+        assert_eq!(
+            captured.get::<u8>(1).unwrap_err().to_string(),
+            "line 3, column 5: invalid value '300'"
+        );
+
+        assert_eq!(
+            captures(&regex, 2, "mem[8] = 11 ", "expected a write")
+                .err()
+                .map(|e| e.to_string()),
+            Some("line 2, column 1: expected a write".to_string())
+        );
+
+        let writes = lines_matching(&regex, "mem[8] = 11\nmem[7] = 101\n", "", |c| {
+            Ok((c.get::<u64>(1)?, c.get::<u64>(2)?))
+        })
+        .unwrap();
+        assert_eq!(writes, vec![(8, 11), (7, 101)]);
+    }
+}
@@ -0,0 +1,87 @@
+use crate::error::{Error, Result};
+use crate::solver::DynSolver;
+use std::sync::Arc;
+
+pub struct Entry {
+    pub year: u16,
+    pub day: u8,
+    pub solver: fn() -> Arc<dyn DynSolver>,
+}
+
+inventory::collect!(Entry);
+
+// Registers a solver for the given year and day, used as
+// `register_solver!(2020, 1, Problem);` next to the day's `Problem`. It is
+// unused when every year's feature is disabled.
+#[allow(unused_macros)]
+macro_rules! register_solver {
+    ($year:expr, $day:expr, $problem:expr) => {
+        inventory::submit! {
+            $crate::registry::Entry {
+                year: $year,
+                day: $day,
+                solver: || std::sync::Arc::new($problem),
+            }
+        }
+    };
+}
+
+pub fn entries() -> Vec<&'static Entry> {
+    let mut entries: Vec<&'static Entry> = inventory::iter::<Entry>.into_iter().collect();
+    entries.sort_by_key(|e| (e.year, e.day));
+    entries
+}
+
+pub fn find(year: u16, day: u8) -> Option<&'static Entry> {
+    inventory::iter::<Entry>
+        .into_iter()
+        .find(|e| e.year == year && e.day == day)
+}
+
+// Like `find`, with an error for a day that is not implemented
+pub fn find_day(year: u16, day: u8) -> Result<&'static Entry> {
+    find(year, day)
+        .ok_or_else(|| Error::usage(format!("{} day {:02} is not implemented", year, day)))
+}
+
+// Prints the implemented days of every year
+pub fn list_days() {
+    let entries = entries();
+    let mut years: Vec<u16> = entries.iter().map(|e| e.year).collect();
+    years.dedup();
+
+    for year in years {
+        let days: Vec<String> = entries
+            .iter()
+            .filter(|e| e.year == year)
+            .map(|e| e.day.to_string())
+            .collect();
+        println!("{}: {}", year, days.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::*;
+
+    #[test]
+    fn test_entries() {
+        // A year whose feature is disabled registers no days
+        let entries = entries();
+        let count = |year| entries.iter().filter(|e| e.year == year).count();
+        assert_eq!(count(2015), if cfg!(feature = "y2015") { 13 } else { 0 });
+        assert_eq!(count(2020), if cfg!(feature = "y2020") { 25 } else { 0 });
+        if cfg!(feature = "y2015") {
+            assert_eq!((entries[0].year, entries[0].day), (2015, 1));
+        }
+        assert!(entries
+            .windows(2)
+            .all(|w| (w[0].year, w[0].day) < (w[1].year, w[1].day)));
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(2020, 25).is_some(), cfg!(feature = "y2020"));
+        assert!(find(2020, 26).is_none());
+    }
+}
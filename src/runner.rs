@@ -0,0 +1,737 @@
+use crate::cache;
+use crate::cache::Cache;
+use crate::cancel;
+use crate::error::{Error, ErrorKind, Result};
+use crate::inputs;
+use crate::inputs::{Expected, InputSet, DEFAULT_INPUT};
+use crate::memory;
+use crate::memory::AllocStats;
+use crate::output;
+use crate::output::Format;
+use crate::registry;
+use crate::registry::Entry;
+use crate::solver::{DynSolver, ParsedInput, Part};
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub struct PartResult {
+    pub part: Part,
+    pub answer: Result<String>,
+    pub duration: Duration,
+    pub memory: Option<AllocStats>,
+    pub cached: bool,
+}
+
+pub struct DayResult {
+    pub parse: Duration,
+    pub parse_memory: Option<AllocStats>,
+    pub parts: Vec<PartResult>,
+}
+
+pub struct DayReport {
+    pub year: u16,
+    pub day: u8,
+    pub input: String,
+    pub result: Result<DayResult>,
+}
+
+impl DayReport {
+    pub fn is_ok(&self) -> bool {
+        match &self.result {
+            Ok(result) => result.parts.iter().all(|p| p.answer.is_ok()),
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Panic,
+    Timeout,
+}
+
+impl Status {
+    pub fn of<T>(result: &Result<T>) -> Status {
+        match result {
+            Ok(_) => Status::Ok,
+            Err(Error {
+                kind: ErrorKind::Panic(_),
+                ..
+            }) => Status::Panic,
+            Err(Error {
+                kind: ErrorKind::Timeout(_),
+                ..
+            }) => Status::Timeout,
+            Err(_) => Status::Error,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Ok => write!(f, "OK"),
+            Status::Error => write!(f, "ERROR"),
+            Status::Panic => write!(f, "PANIC"),
+            Status::Timeout => write!(f, "TIMEOUT"),
+        }
+    }
+}
+
+// Parses the input and solves the requested parts, timing every step separately.
+// A panic while parsing or solving is turned into an error for that step. With a
+// `timeout`, each part is solved on a worker thread and reported as timed out
+// once it has taken longer, whether or not the solver checks for cancellation.
+// Allocations are counted per step when the counting allocator is installed.
+pub fn run(
+    solver: Arc<dyn DynSolver>,
+    year: u16,
+    day: u8,
+    input: &str,
+    parts: &[Part],
+    timeout: Option<Duration>,
+) -> Result<DayResult> {
+    let start = Instant::now();
+    let (parsed, parse_memory) = memory::measure(|| catch_panic(|| solver.parse(input)));
+    let parsed = parsed.map_err(|e| e.with_day(year, day))?;
+    let parse = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let (answer, memory) = match timeout {
+                Some(timeout) => {
+                    let (solver, parsed) = (solver.clone(), parsed.clone());
+                    cancel::with_timeout(timeout, move || solve(solver.as_ref(), part, &parsed))
+                        .unwrap_or((Err(Error::timeout(timeout)), None))
+                }
+                None => solve(solver.as_ref(), part, &parsed),
+            };
+            PartResult {
+                part,
+                answer: answer.map_err(|e| e.with_day(year, day).with_part(part)),
+                duration: start.elapsed(),
+                memory,
+                cached: false,
+            }
+        })
+        .collect();
+
+    Ok(DayResult {
+        parse,
+        parse_memory,
+        parts,
+    })
+}
+
+fn solve(
+    solver: &dyn DynSolver,
+    part: Part,
+    parsed: &ParsedInput,
+) -> (Result<String>, Option<AllocStats>) {
+    memory::measure(|| catch_panic(|| solver.solve_part(part, parsed)))
+}
+
+thread_local! {
+    static PANIC_LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(panic::AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        let message = panic_message(payload.as_ref());
+        match PANIC_LOCATION.with(|l| l.borrow_mut().take()) {
+            Some(location) => Err(Error::panic(format!("{} at {}", message, location))),
+            None => Err(Error::panic(message)),
+        }
+    })
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+// Turns the answers of a named input that differ from the ones expected in its
+// `<name>.toml` into errors, as verify would report them
+fn check_expected(year: u16, day: u8, set: &InputSet, result: &mut Result<DayResult>) {
+    let (path, day_result) = match (set.answers_path(), result.as_mut()) {
+        (Some(path), Ok(day_result)) => (path, day_result),
+        _ => return,
+    };
+    let expected = match Expected::load(&path) {
+        Ok(expected) => expected,
+        Err(error) => {
+            *result = Err(error.with_day(year, day));
+            return;
+        }
+    };
+    for part in &mut day_result.parts {
+        if let (Ok(answer), Some(expected)) = (&part.answer, expected.get(part.part)) {
+            if answer != expected {
+                part.answer = Err(Error::solve(format!(
+                    "{} does not match {} expected in {}",
+                    answer, expected, path
+                ))
+                .with_day(year, day)
+                .with_part(part.part));
+            }
+        }
+    }
+}
+
+// Prints the answers of every input side by side, one row per part, so inputs
+// of different accounts can be compared at a glance
+fn print_comparison(reports: &[DayReport]) {
+    let cells: Vec<Vec<String>> = reports
+        .iter()
+        .map(|report| match &report.result {
+            Ok(result) => result
+                .parts
+                .iter()
+                .map(|p| match &p.answer {
+                    Ok(answer) => answer.clone(),
+                    Err(_) => String::from("error"),
+                })
+                .collect(),
+            Err(_) => Vec::new(),
+        })
+        .collect();
+    let widths: Vec<usize> = reports
+        .iter()
+        .zip(&cells)
+        .map(|(report, answers)| {
+            answers
+                .iter()
+                .map(|a| a.len())
+                .chain(std::iter::once(report.input.len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    print!("{:<6}", "part");
+    for (report, width) in reports.iter().zip(&widths) {
+        print!("{:<w$}  ", report.input, w = width);
+    }
+    println!();
+    let parts = cells.iter().map(|c| c.len()).max().unwrap_or(0);
+    for row in 0..parts {
+        print!("{:<6}", row + 1);
+        for (answers, width) in cells.iter().zip(&widths) {
+            let answer = answers.get(row).map_or("-", |a| a.as_str());
+            print!("{:<w$}  ", answer, w = width);
+        }
+        println!();
+    }
+}
+
+// Runs `f` with a panic hook that records where a panic happened for
+// `catch_panic` instead of printing it, and restores the previous hook after
+fn with_panic_locations<T>(f: impl FnOnce() -> T) -> T {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        let location = info.location().map(|l| l.to_string());
+        PANIC_LOCATION.with(|l| *l.borrow_mut() = location);
+    }));
+    let result = f();
+    panic::set_hook(hook);
+    result
+}
+
+// Runs every day on each of its inputs on `jobs` threads and returns the reports
+// in the order of `tasks`, taking answers from `cache` when given. While running,
+// panics are recorded in the reports instead of being printed by the default
+// panic hook.
+pub fn run_all<F>(
+    tasks: &[(&'static Entry, InputSet)],
+    jobs: usize,
+    timeout: Option<Duration>,
+    cache: Option<&Mutex<Cache>>,
+    load_input: F,
+) -> Vec<DayReport>
+where
+    F: Fn(&Entry, &InputSet) -> Result<String> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new(Vec::with_capacity(tasks.len()));
+
+    with_panic_locations(|| {
+        thread::scope(|scope| {
+            for _ in 0..jobs.min(tasks.len()) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let (entry, set) = match tasks.get(i) {
+                        Some(task) => task,
+                        None => break,
+                    };
+                    let mut result = catch_panic(|| load_input(entry, set)).and_then(|input| {
+                        let solver = (entry.solver)();
+                        let parts = [Part::One, Part::Two];
+                        match cache {
+                            Some(cache) => cache::run(
+                                cache, solver, entry.year, entry.day, &input, &parts, timeout,
+                            ),
+                            None => run(solver, entry.year, entry.day, &input, &parts, timeout),
+                        }
+                    });
+                    check_expected(entry.year, entry.day, set, &mut result);
+                    let report = DayReport {
+                        year: entry.year,
+                        day: entry.day,
+                        input: set.name.clone(),
+                        result,
+                    };
+                    reports.lock().unwrap().push((i, report));
+                });
+            }
+        })
+    });
+
+    let mut reports = reports.into_inner().unwrap();
+    reports.sort_by_key(|(i, _)| *i);
+    reports.into_iter().map(|(_, report)| report).collect()
+}
+
+// Prints one row per part, or a single row when the input could not be loaded
+// or parsed. The input column is only shown when some day has named inputs.
+pub fn print_table(reports: &[DayReport]) {
+    let input_width = if reports.iter().all(|r| r.input == DEFAULT_INPUT) {
+        0
+    } else {
+        reports.iter().map(|r| r.input.len() + 2).max().unwrap_or(0)
+    };
+    println!(
+        "{:<6}{:>4}  {:<w$}{:>4}  {:<7}{:>10}  answer",
+        "year",
+        "day",
+        if input_width > 0 { "input" } else { "" },
+        "part",
+        "status",
+        "time",
+        w = input_width
+    );
+    for report in reports {
+        let rows: Vec<(String, Status, String, String)> = match &report.result {
+            Ok(result) => result
+                .parts
+                .iter()
+                .map(|p| {
+                    let answer = match &p.answer {
+                        Ok(answer) => answer.clone(),
+                        Err(error) => error.to_string(),
+                    };
+                    let time = if p.cached {
+                        String::from("cached")
+                    } else {
+                        format_duration(p.duration)
+                    };
+                    (p.part.to_string(), Status::of(&p.answer), time, answer)
+                })
+                .collect(),
+            Err(error) => vec![(
+                String::from("-"),
+                Status::of(&report.result),
+                String::from("-"),
+                error.to_string(),
+            )],
+        };
+        for (part, status, time, answer) in rows {
+            println!(
+                "{:<6}{:>4}  {:<w$}{:>4}  {:<7}{:>10}  {}",
+                report.year,
+                format!("{:02}", report.day),
+                if input_width > 0 { &report.input } else { "" },
+                part,
+                status.to_string(),
+                time,
+                answer,
+                w = input_width
+            );
+        }
+    }
+}
+
+// Time spent parsing and solving across all reports, which exceeds the wall
+// time when days ran in parallel
+pub fn total_duration(reports: &[DayReport]) -> Duration {
+    reports
+        .iter()
+        .filter_map(|r| r.result.as_ref().ok())
+        .map(|r| r.parse + r.parts.iter().map(|p| p.duration).sum::<Duration>())
+        .sum()
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    if nanos < 1_000.0 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000.0 {
+        format!("{:.1}µs", nanos / 1_000.0)
+    } else if nanos < 1_000_000_000.0 {
+        format!("{:.1}ms", nanos / 1_000_000.0)
+    } else {
+        format!("{:.2}s", nanos / 1_000_000_000.0)
+    }
+}
+
+// Solves one day on its default and named inputs, or on `input_path` when given,
+// printing the answers in `format`
+pub fn run_day(
+    year: u16,
+    day: u8,
+    part: Option<Part>,
+    input_path: Option<String>,
+    format: Format,
+    timeout: Option<Duration>,
+    no_cache: bool,
+) -> Result<()> {
+    let entry = registry::find_day(year, day)?;
+    let sets = match input_path {
+        Some(path) => vec![InputSet::new(&path, &path)],
+        None => inputs::input_sets(year, day)?,
+    };
+    let parts = match part {
+        Some(part) => vec![part],
+        None => vec![Part::One, Part::Two],
+    };
+
+    let cache = cache::open(no_cache)?;
+    // Panics are reported like in run-all rather than printed by the default hook
+    let reports: Vec<DayReport> = with_panic_locations(|| {
+        sets.iter()
+            .map(|set| {
+                let mut result = set
+                    .load()
+                    .map_err(|e| e.with_day(year, day))
+                    .and_then(|input| {
+                        let solver = (entry.solver)();
+                        match &cache {
+                            Some(cache) => {
+                                cache::run(cache, solver, year, day, &input, &parts, timeout)
+                            }
+                            None => run(solver, year, day, &input, &parts, timeout),
+                        }
+                    });
+                check_expected(year, day, set, &mut result);
+                DayReport {
+                    year,
+                    day,
+                    input: set.name.clone(),
+                    result,
+                }
+            })
+            .collect()
+    });
+    cache::save_changes(cache)?;
+    if format != Format::Text {
+        output::print_records(format, &reports.iter().collect::<Vec<_>>())?;
+    }
+
+    // A single input keeps the plain output and stops at its first error
+    if reports.len() == 1 {
+        let report = reports.into_iter().next().unwrap();
+        let result = report.result?;
+        if format == Format::Text {
+            println!("Parse: {}", parse_time(&result));
+        }
+        for part in result.parts {
+            let time = part_time(&part);
+            let answer = part.answer?;
+            if format == Format::Text {
+                println!("Part {}: {} ({})", part.part, answer, time);
+            }
+        }
+        return Ok(());
+    }
+
+    if format == Format::Text {
+        for (i, (set, report)) in sets.iter().zip(&reports).enumerate() {
+            if i > 0 {
+                println!();
+            }
+            println!("{}", set.path);
+            match &report.result {
+                Ok(result) => {
+                    println!("Parse: {}", parse_time(result));
+                    for part in &result.parts {
+                        match &part.answer {
+                            Ok(answer) => {
+                                println!("Part {}: {} ({})", part.part, answer, part_time(part))
+                            }
+                            Err(error) => println!("Part {}: error: {}", part.part, error),
+                        }
+                    }
+                }
+                Err(error) => println!("error: {}", error),
+            }
+        }
+        println!();
+        print_comparison(&reports);
+    }
+    let failed = reports.iter().filter(|r| !r.is_ok()).count();
+    if failed > 0 {
+        return Err(Error::solve(format!(
+            "{} of {} inputs failed",
+            failed,
+            reports.len()
+        )));
+    }
+    Ok(())
+}
+
+// Solves every implemented day of `year`, or of every year, on `jobs` threads
+// and prints a table or records of the answers
+pub fn run_days(
+    year: Option<u16>,
+    jobs: Option<usize>,
+    format: Format,
+    timeout: Option<Duration>,
+    no_cache: bool,
+) -> Result<()> {
+    let entries: Vec<_> = registry::entries()
+        .into_iter()
+        .filter(|e| year.is_none_or(|y| e.year == y))
+        .collect();
+    if entries.is_empty() {
+        return Err(Error::usage(format!(
+            "no days are implemented for {}",
+            year.unwrap_or_default()
+        )));
+    }
+
+    let mut tasks = Vec::new();
+    for entry in &entries {
+        for set in inputs::input_sets(entry.year, entry.day)? {
+            tasks.push((*entry, set));
+        }
+    }
+    // Days are counted by input once any day has more than one
+    let noun = if tasks.len() == entries.len() {
+        "days"
+    } else {
+        "inputs"
+    };
+
+    let jobs = jobs.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    let cache = cache::open(no_cache)?;
+    let start = Instant::now();
+    let reports = run_all(&tasks, jobs, timeout, cache.as_ref(), |entry, set| {
+        set.load().map_err(|e| e.with_day(entry.year, entry.day))
+    });
+    let wall_time = start.elapsed();
+    cache::save_changes(cache)?;
+
+    if format == Format::Text {
+        print_table(&reports);
+        let threads = jobs.min(tasks.len());
+        println!(
+            "{} {} in {} wall time on {} thread{} ({} parsing and solving)",
+            tasks.len(),
+            noun,
+            format_duration(wall_time),
+            threads,
+            if threads == 1 { "" } else { "s" },
+            format_duration(total_duration(&reports))
+        );
+    } else {
+        output::print_records(format, &reports.iter().collect::<Vec<_>>())?;
+    }
+
+    let failed = reports.iter().filter(|r| !r.is_ok()).count();
+
+    if failed > 0 {
+        return Err(Error::solve(format!(
+            "{} of {} {} failed",
+            failed,
+            tasks.len(),
+            noun
+        )));
+    }
+    Ok(())
+}
+
+// The input is not parsed when every answer came from the cache
+fn parse_time(result: &DayResult) -> String {
+    if result.parts.iter().all(|p| p.cached) {
+        String::from("skipped")
+    } else {
+        format_duration(result.parse)
+    }
+}
+
+// Duration of a part, or a note that its answer came from the cache
+fn part_time(part: &PartResult) -> String {
+    if part.cached {
+        String::from("cached")
+    } else {
+        format_duration(part.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::runner::*;
+
+    #[test]
+    #[cfg(feature = "y2015")]
+    fn test_run() {
+        let result = run(
+            Arc::new(crate::y2015::day01::Problem),
+            2015,
+            1,
+            "()())",
+            &[Part::One, Part::Two],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(result.parts.len(), 2);
+        assert_eq!(result.parts[0].answer.as_ref().unwrap(), "-1");
+        assert_eq!(result.parts[1].answer.as_ref().unwrap(), "5");
+    }
+
+    #[test]
+    #[cfg(feature = "y2015")]
+    fn test_check_expected() {
+        let dir = std::env::temp_dir().join("aoc-check-expected-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("alice.txt");
+        std::fs::write(dir.join("alice.toml"), "part1 = \"-1\"\npart2 = \"7\"\n").unwrap();
+
+        let set = InputSet::new("alice", path.to_str().unwrap());
+        let parts = [Part::One, Part::Two];
+        let solver = Arc::new(crate::y2015::day01::Problem);
+        let mut result = run(solver, 2015, 1, "()())", &parts, None);
+        check_expected(2015, 1, &set, &mut result);
+        let result = result.unwrap();
+        assert_eq!(result.parts[0].answer.as_ref().unwrap(), "-1");
+        assert!(result.parts[1]
+            .answer
+            .as_ref()
+            .unwrap_err()
+            .to_string()
+            .contains("5 does not match 7 expected in"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "y2015", feature = "y2020"))]
+    fn test_run_all_catches_panics() {
+        let tasks: Vec<_> = [(2015, 1), (2020, 1)]
+            .iter()
+            .map(|&(year, day)| {
+                let path = crate::solver::get_input_file_path(&year, &day);
+                (
+                    crate::registry::find(year, day).unwrap(),
+                    InputSet::new(DEFAULT_INPUT, &path),
+                )
+            })
+            .collect();
+        let reports = run_all(&tasks, 2, None, None, |entry, _| {
+            if entry.year == 2020 {
+                panic!("no input for {}", entry.year);
+            }
+            Ok(String::from("()())"))
+        });
+
+        assert_eq!(reports.len(), 2);
+        let parts = &reports[0].result.as_ref().unwrap().parts;
+        assert_eq!(parts[1].answer.as_ref().unwrap(), "5");
+        assert_eq!(Status::of(&parts[1].answer), Status::Ok);
+        assert_eq!((reports[1].year, reports[1].day), (2020, 1));
+        assert_eq!(reports[1].input, "default");
+        assert_eq!(Status::of(&reports[1].result), Status::Panic);
+        assert!(reports[1]
+            .result
+            .as_ref()
+            .err()
+            .unwrap()
+            .to_string()
+            .starts_with("panicked: no input for 2020 at src/runner.rs:"));
+    }
+
+    #[test]
+    #[cfg(feature = "y2015")]
+    fn test_run_timeout() {
+        // Finding a hash starting with six zeros takes seconds
+        let result = run(
+            Arc::new(crate::y2015::day04::Problem),
+            2015,
+            4,
+            "abcdef\n",
+            &[Part::Two],
+            Some(Duration::from_millis(50)),
+        )
+        .unwrap();
+
+        let answer = &result.parts[0].answer;
+        assert_eq!(Status::of(answer), Status::Timeout);
+        assert_eq!(
+            answer.as_ref().unwrap_err().to_string(),
+            "2015 day 04, part 2: timed out after 50.0ms"
+        );
+    }
+
+    // Never checks for cancellation
+    struct Sleeper;
+
+    impl crate::solver::Solver for Sleeper {
+        type Input = ();
+        type Output1 = usize;
+        type Output2 = usize;
+
+        fn parse_input(&self, _reader: &mut dyn std::io::BufRead) -> Result<()> {
+            Ok(())
+        }
+
+        fn solve_part_one(&self, _input: &()) -> Result<usize> {
+            thread::sleep(Duration::from_secs(10));
+            Ok(1)
+        }
+
+        fn solve_part_two(&self, _input: &()) -> Result<usize> {
+            Ok(2)
+        }
+    }
+
+    #[test]
+    fn test_run_timeout_without_cancellation() {
+        let start = Instant::now();
+        let result = run(
+            Arc::new(Sleeper),
+            2015,
+            1,
+            "",
+            &[Part::One, Part::Two],
+            Some(Duration::from_millis(50)),
+        )
+        .unwrap();
+
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(Status::of(&result.parts[0].answer), Status::Timeout);
+        assert_eq!(result.parts[1].answer.as_ref().unwrap(), "2");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(250)), "250ns");
+        assert_eq!(format_duration(Duration::from_nanos(12_340)), "12.3µs");
+        assert_eq!(format_duration(Duration::from_micros(4_560)), "4.6ms");
+        assert_eq!(format_duration(Duration::from_millis(2_500)), "2.50s");
+    }
+}
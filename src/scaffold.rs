@@ -0,0 +1,178 @@
+use crate::error::{Error, Result};
+use crate::solver;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const DAY_TEMPLATE: &str = "\
+use crate::error::{Error, Result};
+use crate::solver::Solver;
+use std::io::prelude::*;
+
+pub struct Problem;
+
+register_solver!({year}, {day}, Problem);
+
+impl Solver for Problem {
+    type Input = Vec<String>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader.lines().map(|l| Ok(l?)).collect()
+    }
+
+    fn solve_part_one(&self, _input: &Vec<String>) -> Result<usize> {
+        Err(Error::solve(\"part 1 is not solved yet\"))
+    }
+
+    fn solve_part_two(&self, _input: &Vec<String>) -> Result<usize> {
+        Err(Error::solve(\"part 2 is not solved yet\"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::y{year}::day{day:02}::*;
+
+    const EXAMPLE: &str = \"\";
+
+    #[test]
+    #[ignore = \"fill in the example and its answers\"]
+    fn test_example() {
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(Problem.solve_part_one(&ex).unwrap(), 0);
+        assert_eq!(Problem.solve_part_two(&ex).unwrap(), 0);
+    }
+}
+";
+
+// Source of a new day with a stubbed `Solver` and an ignored example test
+pub fn day_source(year: u16, day: u8) -> String {
+    DAY_TEMPLATE
+        .replace("{year}", &year.to_string())
+        .replace("{day:02}", &format!("{:02}", day))
+        .replace("{day}", &day.to_string())
+}
+
+// Creates `src_dir/yYYYY/dayNN.rs`, declares it in the year's mod.rs and
+// creates an empty input file, returning the created paths
+pub fn new_day(src_dir: &Path, input_path: &Path, year: u16, day: u8) -> Result<Vec<String>> {
+    let year_dir = src_dir.join(format!("y{}", year));
+    let day_path = year_dir.join(format!("day{:02}.rs", day));
+    if day_path.exists() {
+        return Err(Error::usage(format!(
+            "{} already exists",
+            day_path.display()
+        )));
+    }
+
+    let mut created = Vec::new();
+    if !year_dir.exists() {
+        create_dir(&year_dir)?;
+        created.push(year_dir.display().to_string());
+    }
+    let mod_path = year_dir.join("mod.rs");
+    if !mod_path.exists() {
+        created.push(mod_path.display().to_string());
+    }
+    declare_day(&mod_path, day)?;
+    write(&day_path, &day_source(year, day))?;
+    created.push(day_path.display().to_string());
+
+    if !input_path.exists() {
+        if let Some(dir) = input_path.parent() {
+            create_dir(dir)?;
+        }
+        write(input_path, "")?;
+        created.push(input_path.display().to_string());
+    }
+    Ok(created)
+}
+
+// Adds `pub mod dayNN;` to the year's mod.rs before the first later day
+fn declare_day(mod_path: &Path, day: u8) -> Result<()> {
+    let display = mod_path.display().to_string();
+    let contents = match fs::read_to_string(mod_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(Error::io(&display, e)),
+    };
+
+    let declaration = format!("pub mod day{:02};", day);
+    let mut lines: Vec<&str> = contents.lines().collect();
+    let position = lines
+        .iter()
+        .position(|line| line.starts_with("pub mod day") && *line > declaration.as_str())
+        .unwrap_or(lines.len());
+    lines.insert(position, &declaration);
+    write(mod_path, &(lines.join("\n") + "\n"))
+}
+
+fn create_dir(path: &Path) -> Result<()> {
+    fs::create_dir_all(path).map_err(|e| Error::io(&path.display().to_string(), e))
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).map_err(|e| Error::io(&path.display().to_string(), e))
+}
+
+// Creates a day in this repository, printing the created paths
+pub fn create_day(year: u16, day: u8) -> Result<()> {
+    let input_path = solver::get_input_file_path(&year, &day);
+    for path in new_day(Path::new("src"), Path::new(&input_path), year, day)? {
+        println!("Created {}", path);
+    }
+
+    let year_module = format!("pub mod y{};", year);
+    if !fs::read_to_string("src/lib.rs").is_ok_and(|lib| lib.contains(&year_module)) {
+        println!("Declare the new year with `{}` in src/lib.rs", year_module);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scaffold::*;
+
+    #[test]
+    fn test_day_source() {
+        let source = day_source(2016, 7);
+
+        assert!(source.contains("register_solver!(2016, 7, Problem);"));
+        assert!(source.contains("use crate::y2016::day07::*;"));
+        assert!(!source.contains("{day"));
+    }
+
+    #[test]
+    fn test_new_day() {
+        let dir = std::env::temp_dir().join("aoc-scaffold-test");
+        let _ = fs::remove_dir_all(&dir);
+        let src_dir = dir.join("src");
+        let input_path = dir.join("input").join("2016").join("day07.txt");
+        fs::create_dir_all(&src_dir).unwrap();
+
+        let created = new_day(&src_dir, &input_path, 2016, 7).unwrap();
+        assert_eq!(created.len(), 4);
+        assert_eq!(
+            fs::read_to_string(src_dir.join("y2016").join("day07.rs")).unwrap(),
+            day_source(2016, 7)
+        );
+        assert_eq!(fs::read_to_string(&input_path).unwrap(), "");
+
+        assert!(new_day(&src_dir, &input_path, 2016, 7).is_err());
+        // An existing year directory and input file are left alone
+        let created = new_day(&src_dir, &input_path, 2016, 8).unwrap();
+        assert_eq!(
+            created,
+            vec![src_dir.join("y2016").join("day08.rs").display().to_string()]
+        );
+        new_day(&src_dir, &input_path, 2016, 1).unwrap();
+        assert_eq!(
+            fs::read_to_string(src_dir.join("y2016").join("mod.rs")).unwrap(),
+            "pub mod day01;\npub mod day07;\npub mod day08;\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
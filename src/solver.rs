@@ -1,33 +1,145 @@
+use crate::error::{Error, Result};
+use std::any::Any;
+use std::fmt;
 use std::fmt::Display;
-use std::fs::File;
+use std::fs;
 use std::io;
+use std::io::prelude::*;
+use std::sync::Arc;
 
-fn get_input_file_path(year: &u16, day: &u8) -> String {
+pub fn get_input_file_path(year: &u16, day: &u8) -> String {
     format!("input/{}/day{:02}.txt", year, day)
 }
 
+pub fn get_examples_dir_path(year: &u16, day: &u8) -> String {
+    format!("examples/{}/day{:02}", year, day)
+}
+
+pub fn load_input(year: &u16, day: &u8) -> Result<String> {
+    read_input(&get_input_file_path(year, day))
+}
+
+// Reads the input from a file, or from standard input when the path is `-`
+pub fn read_input(file_path: &str) -> Result<String> {
+    if file_path == "-" {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| Error::io("standard input", e))?;
+        return Ok(input);
+    }
+
+    fs::read_to_string(file_path).map_err(|e| Error::io(file_path, e))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
 pub trait Solver {
     type Input;
     type Output1: Display;
     type Output2: Display;
 
-    fn parse_input(&self, file: File) -> Self::Input;
-    fn solve_part_one(&self, input: &Self::Input) -> Self::Output1;
-    fn solve_part_two(&self, input: &Self::Input) -> Self::Output2;
+    // Part of the key of cached answers. Bump it when a change to the solver can
+    // change its answers, so that answers cached by the old code are ignored.
+    const VERSION: &'static str = "1";
+
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Self::Input>;
+    fn solve_part_one(&self, input: &Self::Input) -> Result<Self::Output1>;
+    fn solve_part_two(&self, input: &Self::Input) -> Result<Self::Output2>;
+
+    fn parse_str(&self, input: &str) -> Result<Self::Input> {
+        self.parse_input(&mut input.as_bytes())
+    }
+}
+
+// Parsed input of a type-erased solver, only usable with the solver that produced it.
+// Clones share the input, so a part can be solved on another thread.
+#[derive(Clone)]
+pub struct ParsedInput(Arc<dyn Any + Send + Sync>);
+
+// Object-safe view of a `Solver` so that different days can be stored and run together
+pub trait DynSolver: Send + Sync {
+    fn version(&self) -> &'static str;
+    fn parse(&self, input: &str) -> Result<ParsedInput>;
+    fn part_one(&self, input: &ParsedInput) -> Result<String>;
+    fn part_two(&self, input: &ParsedInput) -> Result<String>;
+
+    fn solve_part(&self, part: Part, input: &ParsedInput) -> Result<String> {
+        match part {
+            Part::One => self.part_one(input),
+            Part::Two => self.part_two(input),
+        }
+    }
+}
+
+impl<S> DynSolver for S
+where
+    S: Solver + Send + Sync,
+    S::Input: Send + Sync + 'static,
+{
+    fn version(&self) -> &'static str {
+        S::VERSION
+    }
+
+    fn parse(&self, input: &str) -> Result<ParsedInput> {
+        let input = self.parse_str(input)?;
+        Ok(ParsedInput(Arc::new(input)))
+    }
 
-    fn load_input(&self, file_path: String) -> io::Result<Self::Input> {
-        let file = File::open(file_path)?;
-        Ok(self.parse_input(file))
+    fn part_one(&self, input: &ParsedInput) -> Result<String> {
+        let answer = self.solve_part_one(downcast_input::<S>(input)?)?;
+        Ok(answer.to_string())
     }
 
-    fn solve(&self, year: &u16, day: &u8) {
-        let input_file_path = get_input_file_path(year, day);
-        let input = self
-            .load_input(input_file_path)
-            .expect("Unable to open file");
-        let part_1_solution = self.solve_part_one(&input);
-        let part_2_solution = self.solve_part_two(&input);
-        println!("Part 1: {}", part_1_solution);
-        println!("Part 2: {}", part_2_solution);
+    fn part_two(&self, input: &ParsedInput) -> Result<String> {
+        let answer = self.solve_part_two(downcast_input::<S>(input)?)?;
+        Ok(answer.to_string())
+    }
+}
+
+fn downcast_input<S>(input: &ParsedInput) -> Result<&S::Input>
+where
+    S: Solver,
+    S::Input: 'static,
+{
+    input
+        .0
+        .downcast_ref()
+        .ok_or_else(|| Error::solve("input was parsed by a different solver"))
+}
+
+#[cfg(all(test, feature = "y2015", feature = "y2020"))]
+mod tests {
+    use crate::solver::*;
+
+    #[test]
+    fn test_dyn_solver() {
+        let solvers: Vec<Arc<dyn DynSolver>> = vec![
+            Arc::new(crate::y2015::day01::Problem),
+            Arc::new(crate::y2020::day01::Problem),
+        ];
+
+        let input = solvers[0].parse("(()(()(").unwrap();
+        assert_eq!(solvers[0].part_one(&input).unwrap(), "3");
+        assert!(solvers[1].part_one(&input).is_err());
+
+        let input = solvers[1]
+            .parse("1721\n979\n366\n299\n675\n1456\n")
+            .unwrap();
+        assert_eq!(solvers[1].part_one(&input).unwrap(), "514579");
+        assert_eq!(solvers[1].part_two(&input).unwrap(), "241861950");
     }
 }
@@ -0,0 +1,504 @@
+use crate::answers::Answers;
+use crate::answers::{part_from_key, part_number, table};
+use crate::client::Client;
+use crate::config::Config;
+use crate::error::{Error, Result};
+use crate::registry;
+use crate::runner;
+use crate::solver;
+use crate::solver::Part;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::time::{SystemTime, UNIX_EPOCH};
+use toml::Value;
+
+pub const REJECTED_FILE_PATH: &str = "input/rejected.toml";
+
+// How long to wait after a rate limit that does not say how long it lasts
+const DEFAULT_COOLDOWN_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    Wrong,
+    TooHigh,
+    TooLow,
+}
+
+impl Rejection {
+    fn from_name(name: &str) -> Option<Rejection> {
+        match name {
+            "wrong" => Some(Rejection::Wrong),
+            "too high" => Some(Rejection::TooHigh),
+            "too low" => Some(Rejection::TooLow),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejection::Wrong => write!(f, "wrong"),
+            Rejection::TooHigh => write!(f, "too high"),
+            Rejection::TooLow => write!(f, "too low"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Rejected(Rejection),
+    // How long the server asks to wait, e.g. "44s" or "4m 12s"
+    RateLimited(Option<String>),
+    AlreadySolved,
+    Unknown(String),
+}
+
+impl Verdict {
+    // Reads the verdict from the text of the page returned for a submission
+    pub fn parse(page: &str) -> Verdict {
+        let text = article_text(page);
+        if text.contains("That's the right answer") {
+            Verdict::Correct
+        } else if text.contains("That's not the right answer") {
+            if text.contains("too high") {
+                Verdict::Rejected(Rejection::TooHigh)
+            } else if text.contains("too low") {
+                Verdict::Rejected(Rejection::TooLow)
+            } else {
+                Verdict::Rejected(Rejection::Wrong)
+            }
+        } else if text.contains("You gave an answer too recently") {
+            let wait = text
+                .split("You have ")
+                .nth(1)
+                .and_then(|rest| rest.split(" left to wait").next())
+                .map(|wait| wait.to_string());
+            Verdict::RateLimited(wait)
+        } else if text.contains("Did you already complete it") {
+            Verdict::AlreadySolved
+        } else {
+            Verdict::Unknown(text.chars().take(200).collect())
+        }
+    }
+}
+
+// Reads a wait like "44s" or "4m 12s" as a number of seconds
+fn parse_wait(wait: &str) -> Option<u64> {
+    wait.split_whitespace()
+        .map(|part| {
+            let unit = part.chars().last()?;
+            let number: u64 = part[..part.len() - unit.len_utf8()].parse().ok()?;
+            match unit {
+                'h' => Some(number * 3600),
+                'm' => Some(number * 60),
+                's' => Some(number),
+                _ => None,
+            }
+        })
+        .sum()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+// Text inside the page's <article>, with the tags removed and whitespace collapsed
+fn article_text(page: &str) -> String {
+    let article = match (page.find("<article"), page.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &page[start..end],
+        _ => page,
+    };
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Answers the server rejected, stored as `[2020.day07.part1]` tables mapping
+// each answer to how it was wrong, and the Unix time before which the server
+// rate limits submissions, stored as `cooldown_until`
+#[derive(Debug, Default)]
+pub struct Rejected {
+    rejected: BTreeMap<(u16, u8, u8), BTreeMap<String, Rejection>>,
+    pub cooldown_until: Option<u64>,
+}
+
+impl Rejected {
+    // A missing file is treated as no rejected answers
+    pub fn load(path: &str) -> Result<Rejected> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Rejected::parse(&contents),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Rejected::default()),
+            Err(e) => Err(Error::io(path, e)),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Rejected> {
+        let value: Value = contents.parse()?;
+
+        let mut rejected = Rejected::default();
+        for (year_key, days) in table(&value, "the rejected answers file")? {
+            if year_key == "cooldown_until" {
+                let until = days
+                    .as_integer()
+                    .and_then(|t| u64::try_from(t).ok())
+                    .ok_or_else(|| Error::parse("'cooldown_until' must be a Unix time"))?;
+                rejected.cooldown_until = Some(until);
+                continue;
+            }
+            let year = year_key
+                .parse()
+                .map_err(|_| Error::parse(format!("invalid year '{}'", year_key)))?;
+            for (day_key, parts) in table(days, year_key)? {
+                let day = day_key
+                    .strip_prefix("day")
+                    .and_then(|d| d.parse().ok())
+                    .ok_or_else(|| Error::parse(format!("invalid day '{}'", day_key)))?;
+                for (part_key, answers) in table(parts, day_key)? {
+                    let part = part_from_key(part_key)
+                        .ok_or_else(|| Error::parse(format!("invalid part '{}'", part_key)))?;
+                    for (answer, rejection) in table(answers, part_key)? {
+                        let rejection = rejection
+                            .as_str()
+                            .and_then(Rejection::from_name)
+                            .ok_or_else(|| {
+                                Error::parse(format!(
+                                    "rejection of {}.{}.{}.\"{}\" must be \"wrong\", \"too high\" or \"too low\"",
+                                    year_key, day_key, part_key, answer
+                                ))
+                            })?;
+                        rejected.add(year, day, part, answer, rejection);
+                    }
+                }
+            }
+        }
+
+        Ok(rejected)
+    }
+
+    pub fn add(&mut self, year: u16, day: u8, part: Part, answer: &str, rejection: Rejection) {
+        self.rejected
+            .entry((year, day, part_number(part)))
+            .or_default()
+            .insert(answer.to_string(), rejection);
+    }
+
+    // Explains why `answer` is known to be wrong, either because it was rejected
+    // before or because it is out of the bounds given by earlier too high/too low hints
+    pub fn check(&self, year: u16, day: u8, part: Part, answer: &str) -> Option<String> {
+        let rejected = self.rejected.get(&(year, day, part_number(part)))?;
+        if let Some(rejection) = rejected.get(answer) {
+            return Some(format!(
+                "'{}' was already rejected as {}",
+                answer, rejection
+            ));
+        }
+
+        let value: i64 = answer.parse().ok()?;
+        for (other, rejection) in rejected {
+            let other_value = match other.parse::<i64>() {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            match rejection {
+                Rejection::TooHigh if value >= other_value => {
+                    return Some(format!(
+                        "'{}' is not lower than '{}', which was too high",
+                        answer, other
+                    ))
+                }
+                Rejection::TooLow if value <= other_value => {
+                    return Some(format!(
+                        "'{}' is not higher than '{}', which was too low",
+                        answer, other
+                    ))
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        fs::write(path, self.to_string()).map_err(|e| Error::io(path, e))
+    }
+}
+
+impl fmt::Display for Rejected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(until) = self.cooldown_until {
+            writeln!(f, "cooldown_until = {}", until)?;
+        }
+        for (i, ((year, day, part), answers)) in self.rejected.iter().enumerate() {
+            if i > 0 || self.cooldown_until.is_some() {
+                writeln!(f)?;
+            }
+            writeln!(f, "[{}.day{:02}.part{}]", year, day, part)?;
+            for (answer, rejection) in answers {
+                writeln!(
+                    f,
+                    "{} = {}",
+                    Value::String(answer.clone()),
+                    Value::String(rejection.to_string())
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Solves a part and submits its answer unless it is already known to be
+// accepted or wrong, or the server is still rate limiting submissions. Accepted
+// answers are recorded in `answers_path` and everything else the server said
+// in `state_path`.
+pub fn submit_answer(
+    year: u16,
+    day: u8,
+    part: Part,
+    config_path: &str,
+    answers_path: &str,
+    state_path: &str,
+) -> Result<()> {
+    let entry = registry::find_day(year, day)?;
+    let input = solver::load_input(&year, &day).map_err(|e| e.with_day(year, day))?;
+    let mut result = runner::run((entry.solver)(), year, day, &input, &[part], None)?;
+    let answer = result.parts.remove(0).answer?;
+    println!("Part {}: {}", part, answer);
+
+    let mut answers = Answers::load(answers_path)?;
+    match answers.get(year, day, part) {
+        Some(accepted) if accepted == answer => {
+            println!("Already accepted, not submitting");
+            return Ok(());
+        }
+        Some(accepted) => {
+            return Err(Error::usage(format!(
+                "'{}' was already accepted, not submitting '{}'",
+                accepted, answer
+            ))
+            .with_day(year, day)
+            .with_part(part))
+        }
+        None => {}
+    }
+    let mut rejected = Rejected::load(state_path)?;
+    if let Some(reason) = rejected.check(year, day, part, &answer) {
+        return Err(Error::usage(format!("{}, not submitting", reason))
+            .with_day(year, day)
+            .with_part(part));
+    }
+    if let Some(until) = rejected.cooldown_until.filter(|&until| until > now()) {
+        return Err(Error::usage(format!(
+            "answered too recently, wait {}s before submitting again",
+            until - now()
+        ))
+        .with_day(year, day)
+        .with_part(part));
+    }
+
+    let client = Client::new(&Config::load(config_path)?)?;
+    let page = client
+        .post_answer(year, day, part, &answer)
+        .map_err(|e| e.with_day(year, day).with_part(part))?;
+    let error = match Verdict::parse(&page) {
+        Verdict::Correct => {
+            answers.set(year, day, part, &answer);
+            answers.save(answers_path)?;
+            println!("Correct, recorded in {}", answers_path);
+            return Ok(());
+        }
+        Verdict::Rejected(rejection) => {
+            rejected.add(year, day, part, &answer, rejection);
+            rejected.save(state_path)?;
+            Error::solve(format!("'{}' is {}", answer, rejection))
+        }
+        Verdict::RateLimited(wait) => {
+            let seconds = wait
+                .as_deref()
+                .and_then(parse_wait)
+                .unwrap_or(DEFAULT_COOLDOWN_SECONDS);
+            rejected.cooldown_until = Some(now() + seconds);
+            rejected.save(state_path)?;
+            Error::http(format!(
+                "answered too recently, wait {}s before submitting again",
+                seconds
+            ))
+        }
+        Verdict::AlreadySolved => {
+            Error::http("the server says this part is already solved or still locked")
+        }
+        Verdict::Unknown(text) => Error::http(format!("unrecognised response: {}", text)),
+    };
+    Err(error.with_day(year, day).with_part(part))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::submit::*;
+
+    #[test]
+    fn test_parse_verdict() {
+        let page = |text: &str| format!("<main>\n<article><p>{}</p></article>\n</main>", text);
+
+        assert_eq!(
+            Verdict::parse(&page(
+                "That's the right answer!  You are <span>one gold star</span> closer."
+            )),
+            Verdict::Correct
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "That's not the right answer; your answer is too high.  If you're stuck..."
+            )),
+            Verdict::Rejected(Rejection::TooHigh)
+        );
+        assert_eq!(
+            Verdict::parse(&page("That's not the right answer.  If you're stuck...")),
+            Verdict::Rejected(Rejection::Wrong)
+        );
+        assert_eq!(
+            Verdict::parse(&page("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 12s left to wait.")),
+            Verdict::RateLimited(Some(String::from("4m 12s")))
+        );
+        assert_eq!(
+            Verdict::parse(&page(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            )),
+            Verdict::AlreadySolved
+        );
+        assert_eq!(
+            Verdict::parse("<html>Server  error</html>"),
+            Verdict::Unknown(String::from("Server error"))
+        );
+    }
+
+    #[test]
+    fn test_check_rejected() {
+        let mut rejected = Rejected::default();
+        rejected.add(2020, 9, Part::One, "500", Rejection::TooHigh);
+        rejected.add(2020, 9, Part::One, "100", Rejection::TooLow);
+        rejected.add(2020, 9, Part::One, "250", Rejection::Wrong);
+
+        assert_eq!(
+            rejected.check(2020, 9, Part::One, "250"),
+            Some(String::from("'250' was already rejected as wrong"))
+        );
+        assert_eq!(
+            rejected.check(2020, 9, Part::One, "600"),
+            Some(String::from(
+                "'600' is not lower than '500', which was too high"
+            ))
+        );
+        assert_eq!(
+            rejected.check(2020, 9, Part::One, "100"),
+            Some(String::from("'100' was already rejected as too low"))
+        );
+        assert_eq!(rejected.check(2020, 9, Part::One, "300"), None);
+        assert_eq!(rejected.check(2020, 9, Part::Two, "600"), None);
+    }
+
+    #[test]
+    fn test_rejected_round_trip() {
+        let mut rejected = Rejected::default();
+        rejected.add(2020, 21, Part::Two, "rcqb,cltx", Rejection::Wrong);
+        rejected.add(2015, 1, Part::One, "-138", Rejection::TooLow);
+
+        let text = rejected.to_string();
+        assert_eq!(
+            text,
+            "[2015.day01.part1]\n\"-138\" = \"too low\"\n\n[2020.day21.part2]\n\"rcqb,cltx\" = \"wrong\"\n"
+        );
+        let parsed = Rejected::parse(&text).unwrap();
+        assert_eq!(
+            parsed.check(2015, 1, Part::One, "-200"),
+            Some(String::from(
+                "'-200' is not higher than '-138', which was too low"
+            ))
+        );
+        assert!(parsed.check(2020, 21, Part::Two, "rcqb,cltx").is_some());
+    }
+
+    #[test]
+    fn test_cooldown_round_trip() {
+        assert_eq!(parse_wait("44s"), Some(44));
+        assert_eq!(parse_wait("4m 12s"), Some(252));
+        assert_eq!(parse_wait("soon"), None);
+
+        let mut rejected = Rejected {
+            cooldown_until: Some(1700000000),
+            ..Rejected::default()
+        };
+        rejected.add(2015, 1, Part::One, "-138", Rejection::TooLow);
+        let text = rejected.to_string();
+        assert_eq!(
+            text,
+            "cooldown_until = 1700000000\n\n[2015.day01.part1]\n\"-138\" = \"too low\"\n"
+        );
+        assert_eq!(
+            Rejected::parse(&text).unwrap().cooldown_until,
+            Some(1700000000)
+        );
+    }
+
+    #[cfg(feature = "y2015")]
+    #[test]
+    fn test_submit_answer() {
+        use crate::client::tests::serve_once;
+
+        let dir = std::env::temp_dir().join("aoc-submit-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+        let (config_path, answers_path, state_path) = (
+            path("aoc.toml"),
+            path("answers.toml"),
+            path("rejected.toml"),
+        );
+        let submit = || submit_answer(2015, 1, Part::One, &config_path, &answers_path, &state_path);
+        let serve = |body| {
+            let (base_url, server) = serve_once(200, body);
+            let config = format!("session = \"53616c7465\"\nbase_url = \"{}\"\n", base_url);
+            fs::write(&config_path, config).unwrap();
+            server
+        };
+
+        // A rate limit is remembered, so the second submit never reaches the
+        // server, which is gone by then
+        let server = serve("<article><p>You gave an answer too recently.  You have 4m 12s left to wait.</p></article>");
+        assert!(submit().is_err());
+        assert!(server.join().unwrap().contains("level=1&answer="));
+        let until = Rejected::load(&state_path).unwrap().cooldown_until.unwrap();
+        assert!(until >= now() + 250);
+        assert!(submit()
+            .unwrap_err()
+            .to_string()
+            .contains("answered too recently, wait"));
+
+        // Once the cooldown is over the answer is submitted and recorded
+        fs::write(&state_path, "cooldown_until = 0\n").unwrap();
+        let server = serve("<article><p>That's the right answer!</p></article>");
+        submit().unwrap();
+        server.join().unwrap();
+        let answers = Answers::load(&answers_path).unwrap();
+        assert_eq!(answers.get(2015, 1, Part::One), Some("138"));
+
+        // and is not submitted again
+        submit().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
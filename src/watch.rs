@@ -0,0 +1,243 @@
+use crate::error::Result;
+use crate::examples::Example;
+use crate::inputs;
+use crate::registry;
+use crate::registry::Entry;
+use crate::runner;
+use crate::runner::format_duration;
+use crate::solver;
+use crate::solver::Part;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+// Outcome of running a day on one file, kept to compare with the next run
+#[derive(Debug, Clone, PartialEq)]
+pub enum Summary {
+    Failed(String),
+    Solved {
+        parse: Duration,
+        parts: Vec<(Part, std::result::Result<String, String>, Duration)>,
+    },
+}
+
+pub fn run_file(entry: &Entry, path: &Path) -> Summary {
+    // Examples may start with their expected answers, which are not input
+    let input = if path.starts_with("examples") {
+        Example::load(path).map(|example| example.input)
+    } else {
+        solver::read_input(&path.display().to_string())
+    };
+    let result = input.and_then(|input| {
+        runner::run(
+            (entry.solver)(),
+            entry.year,
+            entry.day,
+            &input,
+            &[Part::One, Part::Two],
+            None,
+        )
+    });
+    match result {
+        Ok(result) => Summary::Solved {
+            parse: result.parse,
+            parts: result
+                .parts
+                .into_iter()
+                .map(|p| (p.part, p.answer.map_err(|e| e.to_string()), p.duration))
+                .collect(),
+        },
+        Err(error) => Summary::Failed(error.to_string()),
+    }
+}
+
+// The day's input, its named inputs and its examples, which may come and go
+// while watching
+pub fn watched_files(year: u16, day: u8) -> Vec<PathBuf> {
+    let mut files = vec![PathBuf::from(solver::get_input_file_path(&year, &day))];
+    for dir in &[
+        inputs::get_input_dir_path(&year, &day),
+        solver::get_examples_dir_path(&year, &day),
+    ] {
+        if let Ok(entries) = fs::read_dir(dir) {
+            let mut found: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+                .collect();
+            found.sort();
+            files.extend(found);
+        }
+    }
+    files
+}
+
+// Modification time and size of every file, `None` for files that are missing
+pub type Stamps = BTreeMap<PathBuf, Option<(SystemTime, u64)>>;
+
+pub fn snapshot(files: &[PathBuf]) -> Stamps {
+    files
+        .iter()
+        .map(|path| {
+            let stamp = fs::metadata(path)
+                .ok()
+                .and_then(|m| Some((m.modified().ok()?, m.len())));
+            (path.clone(), stamp)
+        })
+        .collect()
+}
+
+// Lines describing `current`, with the answers and timings of `previous` when
+// there was an earlier run
+pub fn describe(previous: Option<&Summary>, current: &Summary) -> Vec<String> {
+    let (parse, parts) = match current {
+        Summary::Failed(error) => return vec![format!("error: {}", error)],
+        Summary::Solved { parse, parts } => (parse, parts),
+    };
+    let previous_parts = match previous {
+        Some(Summary::Solved { parse, parts }) => Some((parse, parts)),
+        _ => None,
+    };
+
+    let mut lines = vec![format!(
+        "Parse: {}",
+        timing(previous_parts.map(|(p, _)| *p), *parse)
+    )];
+    for (part, answer, duration) in parts {
+        let before = previous_parts
+            .and_then(|(_, parts)| parts.iter().find(|(p, _, _)| p == part))
+            .map(|(_, answer, duration)| (answer, *duration));
+        let answer = match (before.map(|(a, _)| a), answer) {
+            (Some(Ok(old)), Ok(new)) if old != new => format!("{} -> {}", old, new),
+            (Some(Err(_)), Ok(new)) => format!("error -> {}", new),
+            (Some(Ok(old)), Err(error)) => format!("{} -> error: {}", old, error),
+            (_, Ok(new)) => new.clone(),
+            (_, Err(error)) => format!("error: {}", error),
+        };
+        lines.push(format!(
+            "Part {}: {} ({})",
+            part,
+            answer,
+            timing(before.map(|(_, d)| d), *duration)
+        ));
+    }
+    lines
+}
+
+fn timing(before: Option<Duration>, after: Duration) -> String {
+    match before {
+        Some(before) if before.as_nanos() > 0 => format!(
+            "{} -> {}, {:+.1}%",
+            format_duration(before),
+            format_duration(after),
+            (after.as_nanos() as f64 / before.as_nanos() as f64 - 1.0) * 100.0
+        ),
+        _ => format_duration(after),
+    }
+}
+
+// Polls until interrupted, re-running every file that changed since the last poll
+pub fn watch_day(year: u16, day: u8, interval: Duration) -> Result<()> {
+    let entry = registry::find_day(year, day)?;
+    let source = [PathBuf::from(format!("src/y{}/day{:02}.rs", year, day))];
+    let mut source_stamp = snapshot(&source);
+    let mut stamps = Stamps::new();
+    let mut summaries: BTreeMap<PathBuf, Summary> = BTreeMap::new();
+
+    println!("Watching {} day {:02}, press Ctrl-C to stop", year, day);
+    loop {
+        let current = snapshot(&watched_files(year, day));
+        for (path, stamp) in &current {
+            if stamps.get(path) == Some(stamp) {
+                continue;
+            }
+            println!("\n{}", path.display());
+            if stamp.is_none() {
+                println!("missing");
+                summaries.remove(path);
+                continue;
+            }
+            let summary = run_file(entry, path);
+            for line in describe(summaries.get(path), &summary) {
+                println!("{}", line);
+            }
+            summaries.insert(path.clone(), summary);
+        }
+        for path in stamps.keys().filter(|p| !current.contains_key(*p)) {
+            println!("\n{}\nremoved", path.display());
+            summaries.remove(path);
+        }
+        stamps = current;
+
+        let source_current = snapshot(&source);
+        if source_current != source_stamp {
+            println!(
+                "\n{} changed, rebuild and restart watch to use the new code",
+                source[0].display()
+            );
+            source_stamp = source_current;
+        }
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::watch::*;
+
+    fn solved(parse: u64, parts: Vec<(Part, std::result::Result<&str, &str>, u64)>) -> Summary {
+        Summary::Solved {
+            parse: Duration::from_micros(parse),
+            parts: parts
+                .into_iter()
+                .map(|(part, answer, micros)| {
+                    (
+                        part,
+                        answer.map(String::from).map_err(String::from),
+                        Duration::from_micros(micros),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_describe() {
+        let first = solved(
+            10,
+            vec![
+                (Part::One, Ok("138"), 40),
+                (Part::Two, Err("no basement"), 20),
+            ],
+        );
+        assert_eq!(
+            describe(None, &first),
+            vec![
+                "Parse: 10.0µs",
+                "Part 1: 138 (40.0µs)",
+                "Part 2: error: no basement (20.0µs)"
+            ]
+        );
+
+        let second = solved(
+            10,
+            vec![(Part::One, Ok("138"), 30), (Part::Two, Ok("1771"), 25)],
+        );
+        assert_eq!(
+            describe(Some(&first), &second),
+            vec![
+                "Parse: 10.0µs -> 10.0µs, +0.0%",
+                "Part 1: 138 (40.0µs -> 30.0µs, -25.0%)",
+                "Part 2: error -> 1771 (20.0µs -> 25.0µs, +25.0%)"
+            ]
+        );
+
+        let third = Summary::Failed(String::from("line 1, column 3: unexpected 'x'"));
+        assert_eq!(
+            describe(Some(&second), &third),
+            vec!["error: line 1, column 3: unexpected 'x'"]
+        );
+        assert_eq!(describe(Some(&third), &second)[1], "Part 1: 138 (30.0µs)");
+    }
+}
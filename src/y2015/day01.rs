@@ -1,50 +1,50 @@
+use crate::error::Result;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 1, Problem);
+
 impl Solver for Problem {
     type Input = Vec<char>;
     type Output1 = i32;
     type Output2 = i32;
 
-    fn parse_input(&self, file: File) -> Vec<char> {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<char>> {
         let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents).unwrap();
-        return contents.chars().collect();
+        reader.read_to_string(&mut contents)?;
+        Ok(contents.chars().collect())
     }
 
-    fn solve_part_one(&self, input: &Vec<char>) -> i32 {
-        get_floor(&input)
+    fn solve_part_one(&self, input: &Vec<char>) -> Result<i32> {
+        Ok(get_floor(input))
     }
 
-    fn solve_part_two(&self, input: &Vec<char>) -> i32 {
-        get_basement_position(input)
+    fn solve_part_two(&self, input: &Vec<char>) -> Result<i32> {
+        Ok(get_basement_position(input))
     }
 }
 
-fn get_floor(input: &Vec<char>) -> i32 {
+fn get_floor(input: &[char]) -> i32 {
     let mut level = 0;
     for instruction in input.iter() {
         if *instruction == '(' {
-            level = level + 1;
+            level += 1;
         } else if *instruction == ')' {
-            level = level - 1;
+            level -= 1;
         }
     }
     level
 }
 
-fn get_basement_position(input: &Vec<char>) -> i32 {
+fn get_basement_position(input: &[char]) -> i32 {
     let mut level = 0;
     for (index, instruction) in input.iter().enumerate() {
         if *instruction == '(' {
-            level = level + 1;
+            level += 1;
         } else if *instruction == ')' {
-            level = level - 1;
+            level -= 1;
         }
 
         if level == -1 {
@@ -61,14 +61,14 @@ mod tests {
 
     #[test]
     fn test_get_floor() {
-        let ex1: Vec<char> = "(())".chars().collect();
-        let ex2: Vec<char> = "(((".chars().collect();
-        let ex3: Vec<char> = "(()(()(".chars().collect();
-        let ex4: Vec<char> = "))(((((".chars().collect();
-        let ex5: Vec<char> = "())".chars().collect();
-        let ex6: Vec<char> = "))(".chars().collect();
-        let ex7: Vec<char> = ")))".chars().collect();
-        let ex8: Vec<char> = ")())())".chars().collect();
+        let ex1 = Problem.parse_str("(())").unwrap();
+        let ex2 = Problem.parse_str("(((").unwrap();
+        let ex3 = Problem.parse_str("(()(()(").unwrap();
+        let ex4 = Problem.parse_str("))(((((").unwrap();
+        let ex5 = Problem.parse_str("())").unwrap();
+        let ex6 = Problem.parse_str("))(").unwrap();
+        let ex7 = Problem.parse_str(")))").unwrap();
+        let ex8 = Problem.parse_str(")())())").unwrap();
 
         assert_eq!(get_floor(&ex1), 0);
         assert_eq!(get_floor(&ex2), 3);
@@ -82,8 +82,8 @@ mod tests {
 
     #[test]
     fn test_get_basement_position() {
-        let ex1: Vec<char> = ")".chars().collect();
-        let ex2: Vec<char> = "()())".chars().collect();
+        let ex1 = Problem.parse_str(")").unwrap();
+        let ex2 = Problem.parse_str("()())").unwrap();
 
         assert_eq!(get_basement_position(&ex1), 1);
         assert_eq!(get_basement_position(&ex2), 5);
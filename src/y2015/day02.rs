@@ -1,7 +1,6 @@
+use crate::error::{parse_field, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::BufRead;
-use std::io::BufReader;
 
 pub struct Present {
     dimensions: Vec<u32>,
@@ -34,33 +33,35 @@ impl Present {
 
 pub struct Problem;
 
+register_solver!(2015, 2, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Present>;
     type Output1 = u32;
     type Output2 = u32;
 
-    fn parse_input(&self, file: File) -> Vec<Present> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Present>> {
+        reader
             .lines()
-            .map(|l| {
-                let l = l.unwrap();
+            .enumerate()
+            .map(|(i, l)| {
+                let l = l?;
                 let row_split: Vec<&str> = l.split('x').collect();
-                Present::new(
-                    row_split[0].parse().unwrap(),
-                    row_split[1].parse().unwrap(),
-                    row_split[2].parse().unwrap(),
-                )
+                Ok(Present::new(
+                    parse_field(i + 1, &l, &row_split, 0)?,
+                    parse_field(i + 1, &l, &row_split, 1)?,
+                    parse_field(i + 1, &l, &row_split, 2)?,
+                ))
             })
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<Present>) -> u32 {
-        input.iter().map(|p| p.square_feet_wrapping_paper()).sum()
+    fn solve_part_one(&self, input: &Vec<Present>) -> Result<u32> {
+        Ok(input.iter().map(|p| p.square_feet_wrapping_paper()).sum())
     }
 
-    fn solve_part_two(&self, input: &Vec<Present>) -> u32 {
-        input.iter().map(|p| p.ribbon_length()).sum()
+    fn solve_part_two(&self, input: &Vec<Present>) -> Result<u32> {
+        Ok(input.iter().map(|p| p.ribbon_length()).sum())
     }
 }
 
@@ -70,21 +71,19 @@ mod tests {
 
     #[test]
     fn test_square_feet_wrapping_paper() {
-        let ex1 = Present::new(2, 3, 4);
-        let ex2 = Present::new(1, 1, 10);
-        //let ex3 = Present::new(29, 13, 26);
+        let presents = Problem.parse_str("2x3x4\n1x1x10\n").unwrap();
 
-        assert_eq!(ex1.square_feet_wrapping_paper(), 58);
-        assert_eq!(ex2.square_feet_wrapping_paper(), 43);
+        assert_eq!(presents[0].square_feet_wrapping_paper(), 58);
+        assert_eq!(presents[1].square_feet_wrapping_paper(), 43);
+        assert_eq!(Problem.solve_part_one(&presents).unwrap(), 101);
     }
 
     #[test]
     fn test_ribbon_length() {
-        let ex1 = Present::new(2, 3, 4);
-        let ex2 = Present::new(1, 1, 10);
-        //let ex3 = Present::new(29, 13, 26);
+        let presents = Problem.parse_str("2x3x4\n1x1x10\n").unwrap();
 
-        assert_eq!(ex1.ribbon_length(), 34);
-        assert_eq!(ex2.ribbon_length(), 14);
+        assert_eq!(presents[0].ribbon_length(), 34);
+        assert_eq!(presents[1].ribbon_length(), 14);
+        assert_eq!(Problem.solve_part_two(&presents).unwrap(), 48);
     }
 }
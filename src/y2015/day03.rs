@@ -1,33 +1,42 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
 use std::collections::HashSet;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 3, Problem);
+
 impl Solver for Problem {
     type Input = Vec<char>;
     type Output1 = u32;
     type Output2 = u32;
 
-    fn parse_input(&self, file: File) -> Vec<char> {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<char>> {
         let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents).unwrap();
-        return contents.chars().collect();
+        reader.read_to_string(&mut contents)?;
+        // Only the trailing line end is not a move
+        let moves = contents.trim_end_matches(['\n', '\r']);
+        match moves.find(|c| !"^>v<".contains(c)) {
+            Some(index) => Err(Error::parse_at(
+                1,
+                index + 1,
+                "expected '^', '>', 'v' or '<'",
+            )),
+            None => Ok(moves.chars().collect()),
+        }
     }
 
-    fn solve_part_one(&self, input: &Vec<char>) -> u32 {
-        get_houses(&input)
+    fn solve_part_one(&self, input: &Vec<char>) -> Result<u32> {
+        Ok(get_houses(input))
     }
 
-    fn solve_part_two(&self, input: &Vec<char>) -> u32 {
-        get_houses_robot(&input)
+    fn solve_part_two(&self, input: &Vec<char>) -> Result<u32> {
+        Ok(get_houses_robot(input))
     }
 }
 
-fn get_houses(input: &Vec<char>) -> u32 {
+fn get_houses(input: &[char]) -> u32 {
     let mut houses = HashSet::new();
     let mut current_coordinate = (0, 0);
     houses.insert(current_coordinate);
@@ -48,7 +57,7 @@ fn get_houses(input: &Vec<char>) -> u32 {
     houses.len() as u32
 }
 
-fn get_houses_robot(input: &Vec<char>) -> u32 {
+fn get_houses_robot(input: &[char]) -> u32 {
     let mut houses = HashSet::new();
     let mut coordinates = [(0, 0), (0, 0)];
     let mut pointer = 0;
@@ -78,9 +87,9 @@ mod tests {
 
     #[test]
     fn test_get_houses() {
-        let ex1: Vec<char> = ">".chars().collect();
-        let ex2: Vec<char> = "^>v<".chars().collect();
-        let ex3: Vec<char> = "^v^v^v^v^v".chars().collect();
+        let ex1 = Problem.parse_str(">").unwrap();
+        let ex2 = Problem.parse_str("^>v<").unwrap();
+        let ex3 = Problem.parse_str("^v^v^v^v^v").unwrap();
 
         assert_eq!(get_houses(&ex1), 2);
         assert_eq!(get_houses(&ex2), 4);
@@ -89,12 +98,18 @@ mod tests {
 
     #[test]
     fn test_get_houses_robot() {
-        let ex1: Vec<char> = "^v".chars().collect();
-        let ex2: Vec<char> = "^>v<".chars().collect();
-        let ex3: Vec<char> = "^v^v^v^v^v".chars().collect();
+        let ex1 = Problem.parse_str("^v").unwrap();
+        let ex2 = Problem.parse_str("^>v<").unwrap();
+        let ex3 = Problem.parse_str("^v^v^v^v^v").unwrap();
 
         assert_eq!(get_houses_robot(&ex1), 3);
         assert_eq!(get_houses_robot(&ex2), 3);
         assert_eq!(get_houses_robot(&ex3), 11);
     }
+
+    #[test]
+    fn test_parse_moves() {
+        assert_eq!(Problem.parse_str("^>\n").unwrap(), vec!['^', '>']);
+        assert!(Problem.parse_str("^x>").is_err());
+    }
 }
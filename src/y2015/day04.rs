@@ -1,37 +1,39 @@
+use crate::cancel;
+use crate::error::Result;
 use crate::solver::Solver;
 use md5::{Digest, Md5};
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 4, Problem);
+
 impl Solver for Problem {
     type Input = String;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, file: File) -> String {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<String> {
         let mut contents = String::new();
-        buf_reader.read_to_string(&mut contents).unwrap();
-        contents
+        reader.read_to_string(&mut contents)?;
+        Ok(contents.trim_end().to_string())
     }
 
-    fn solve_part_one(&self, input: &String) -> u64 {
+    fn solve_part_one(&self, input: &String) -> Result<u64> {
         get_lowest_number(input, "00000")
     }
 
-    fn solve_part_two(&self, input: &String) -> u64 {
+    fn solve_part_two(&self, input: &String) -> Result<u64> {
         get_lowest_number(input, "000000")
     }
 }
 
-fn get_lowest_number(secret: &str, start_pattern: &str) -> u64 {
+fn get_lowest_number(secret: &str, start_pattern: &str) -> Result<u64> {
     let mut number = 1;
     let mut hasher = Md5::new();
 
     loop {
+        cancel::check()?;
         hasher.update(format!("{}{}", secret, number));
         let hex = format!("{:x}", hasher.finalize_reset());
 
@@ -39,10 +41,10 @@ fn get_lowest_number(secret: &str, start_pattern: &str) -> u64 {
             break;
         }
 
-        number = number + 1;
+        number += 1;
     }
 
-    number
+    Ok(number)
 }
 
 #[cfg(test)]
@@ -51,10 +53,10 @@ mod tests {
 
     #[test]
     fn test_lowest_number() {
-        let ex1 = "abcdef";
-        let ex2 = "pqrstuv";
+        let ex1 = Problem.parse_str("abcdef\n").unwrap();
+        let ex2 = Problem.parse_str("pqrstuv\n").unwrap();
 
-        assert_eq!(get_lowest_number(&ex1, "00000"), 609043);
-        assert_eq!(get_lowest_number(&ex2, "00000"), 1048970);
+        assert_eq!(get_lowest_number(&ex1, "00000").unwrap(), 609043);
+        assert_eq!(get_lowest_number(&ex2, "00000").unwrap(), 1048970);
     }
 }
@@ -1,31 +1,31 @@
+use crate::error::Result;
 use crate::solver::Solver;
 use std::collections::HashSet;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 5, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader.lines().map(|l| Ok(l?)).collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        input.iter().map(|w| is_word_nice(w)).filter(|w| *w).count()
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input.iter().map(|w| is_word_nice(w)).filter(|w| *w).count())
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
-        input
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input
             .iter()
             .map(|w| new_is_word_nice(w))
             .filter(|w| *w)
-            .count()
+            .count())
     }
 }
 
@@ -41,13 +41,15 @@ fn is_word_nice(word: &str) -> bool {
         }
     }
 
-    for (i, c) in word.to_string().chars().enumerate() {
+    let mut previous = None;
+    for c in word.chars() {
         if vowels.contains(c) {
-            num_vowels = num_vowels + 1;
+            num_vowels += 1;
         }
-        if i > 0 && word.to_string().chars().nth(i - 1).unwrap() == c {
+        if previous == Some(c) {
             has_twice_in_row = true;
         }
+        previous = Some(c);
     }
 
     num_vowels >= 3 && has_twice_in_row
@@ -92,29 +94,30 @@ mod tests {
 
     #[test]
     fn test_word_is_nice() {
-        let ex1 = "ugknbfddgicrmopn";
-        let ex2 = "aaa";
-        let ex3 = "jchzalrnumimnmhp";
-        let ex4 = "haegwjzuvuyypxyu";
-        let ex5 = "dvszwmarrgswjxmb";
-
-        assert_eq!(is_word_nice(ex1), true);
-        assert_eq!(is_word_nice(ex2), true);
-        assert_eq!(is_word_nice(ex3), false);
-        assert_eq!(is_word_nice(ex4), false);
-        assert_eq!(is_word_nice(ex5), false);
+        let words = Problem
+            .parse_str(
+                "ugknbfddgicrmopn\naaa\njchzalrnumimnmhp\nhaegwjzuvuyypxyu\ndvszwmarrgswjxmb\n",
+            )
+            .unwrap();
+
+        assert!(is_word_nice(&words[0]));
+        assert!(is_word_nice(&words[1]));
+        assert!(!is_word_nice(&words[2]));
+        assert!(!is_word_nice(&words[3]));
+        assert!(!is_word_nice(&words[4]));
+        assert_eq!(Problem.solve_part_one(&words).unwrap(), 2);
     }
 
     #[test]
     fn test_new_word_is_nice() {
-        let ex1 = "qjhvhtzxzqqjkmpb";
-        let ex2 = "xxyxx";
-        let ex3 = "uurcxstgmygtbstg";
-        let ex4 = "ieodomkazucvgmuy";
-
-        assert_eq!(new_is_word_nice(ex1), true);
-        assert_eq!(new_is_word_nice(ex2), true);
-        assert_eq!(new_is_word_nice(ex3), false);
-        assert_eq!(new_is_word_nice(ex4), false);
+        let words = Problem
+            .parse_str("qjhvhtzxzqqjkmpb\nxxyxx\nuurcxstgmygtbstg\nieodomkazucvgmuy\n")
+            .unwrap();
+
+        assert!(new_is_word_nice(&words[0]));
+        assert!(new_is_word_nice(&words[1]));
+        assert!(!new_is_word_nice(&words[2]));
+        assert!(!new_is_word_nice(&words[3]));
+        assert_eq!(Problem.solve_part_two(&words).unwrap(), 2);
     }
 }
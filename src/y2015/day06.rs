@@ -1,7 +1,7 @@
+use crate::error::{field, parse_token, Error, Result};
+use crate::grid::Grid;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 enum Operation {
     Off,
@@ -16,107 +16,147 @@ pub struct Instruction {
 }
 
 impl Instruction {
-    fn new(from: &str, to: &str, operation: Operation) -> Instruction {
-        let from_split: Vec<&str> = from.split(',').collect();
-        let to_split: Vec<&str> = to.split(',').collect();
+    fn new(from: (usize, usize), to: (usize, usize), operation: Operation) -> Instruction {
         Instruction {
-            operation: operation,
-            range_from: (
-                from_split[0].parse::<usize>().unwrap(),
-                from_split[1].parse::<usize>().unwrap(),
-            ),
-            range_to: (
-                to_split[0].parse::<usize>().unwrap(),
-                to_split[1].parse::<usize>().unwrap(),
-            ),
+            operation,
+            range_from: from,
+            range_to: to,
         }
     }
+
+    fn parse(line_number: usize, line: &str) -> Result<Instruction> {
+        let row_split: Vec<&str> = line.split(' ').collect();
+        let (operation, from_index) = match field(line_number, line, &row_split, 0)? {
+            "toggle" => (Operation::Toggle, 1),
+            "turn" => match field(line_number, line, &row_split, 1)? {
+                "on" => (Operation::On, 2),
+                "off" => (Operation::Off, 2),
+                other => {
+                    return Err(Error::at_token(
+                        line_number,
+                        line,
+                        other,
+                        format!("unknown operation 'turn {}'", other),
+                    ))
+                }
+            },
+            other => {
+                return Err(Error::at_token(
+                    line_number,
+                    line,
+                    other,
+                    format!("unknown operation '{}'", other),
+                ))
+            }
+        };
+
+        let from = parse_coordinate(
+            line_number,
+            line,
+            field(line_number, line, &row_split, from_index)?,
+        )?;
+        let to = parse_coordinate(
+            line_number,
+            line,
+            field(line_number, line, &row_split, from_index + 2)?,
+        )?;
+        if to.0 >= 1000 || to.1 >= 1000 || from.0 > to.0 || from.1 > to.1 {
+            return Err(Error::parse_at(
+                line_number,
+                1,
+                "range must lie within the 1000x1000 grid",
+            ));
+        }
+
+        Ok(Instruction::new(from, to, operation))
+    }
+}
+
+fn parse_coordinate(line_number: usize, line: &str, coordinate: &str) -> Result<(usize, usize)> {
+    let coordinate_split: Vec<&str> = coordinate.split(',').collect();
+    if coordinate_split.len() != 2 {
+        return Err(Error::at_token(
+            line_number,
+            line,
+            coordinate,
+            format!("invalid coordinate '{}'", coordinate),
+        ));
+    }
+
+    Ok((
+        parse_token(line_number, line, coordinate_split[0])?,
+        parse_token(line_number, line, coordinate_split[1])?,
+    ))
 }
 
 pub struct Problem;
 
+register_solver!(2015, 6, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Instruction>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<Instruction> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Instruction>> {
+        reader
             .lines()
-            .map(|l| {
-                let l = l.unwrap();
-                let row_split: Vec<&str> = l.split(' ').collect();
-                if row_split[0] == "toggle" {
-                    Instruction::new(row_split[1], row_split[3], Operation::Toggle)
-                } else if row_split[1] == "on" {
-                    Instruction::new(row_split[2], row_split[4], Operation::On)
-                } else {
-                    Instruction::new(row_split[2], row_split[4], Operation::Off)
-                }
-            })
+            .enumerate()
+            .map(|(i, l)| Instruction::parse(i + 1, &l?))
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<Instruction>) -> usize {
-        let mut light_grid = vec![vec![false; 1000]; 1000];
+    fn solve_part_one(&self, input: &Vec<Instruction>) -> Result<usize> {
+        let mut light_grid = Grid::new(1000, 1000, false);
         input.iter().for_each(|instruction| {
-            apply_instruction(&instruction, &mut light_grid);
+            apply_instruction(instruction, &mut light_grid);
         });
-        light_grid
-            .iter()
-            .map(|row| row.iter().filter(|l| **l).count())
-            .sum()
+        Ok(light_grid.iter().filter(|l| **l).count())
     }
 
-    fn solve_part_two(&self, input: &Vec<Instruction>) -> usize {
-        let mut light_grid = vec![vec![0; 1000]; 1000];
+    fn solve_part_two(&self, input: &Vec<Instruction>) -> Result<usize> {
+        let mut light_grid = Grid::new(1000, 1000, 0);
         input.iter().for_each(|instruction| {
-            apply_instruction_brightness(&instruction, &mut light_grid);
+            apply_instruction_brightness(instruction, &mut light_grid);
         });
-        light_grid.iter().flat_map(|x: &Vec<_>| x.iter()).sum()
+        Ok(light_grid.iter().sum())
     }
 }
 
-fn apply_instruction(instruction: &Instruction, light_grid: &mut Vec<Vec<bool>>) -> usize {
+fn apply_instruction(instruction: &Instruction, light_grid: &mut Grid<bool>) -> usize {
     let mut lights = 0;
-    for i in instruction.range_from.0..(instruction.range_to.0 + 1) {
-        for j in instruction.range_from.1..(instruction.range_to.1 + 1) {
+    for x in instruction.range_from.0..=instruction.range_to.0 {
+        for y in instruction.range_from.1..=instruction.range_to.1 {
+            let light = &mut light_grid[(x, y)];
             match instruction.operation {
-                Operation::Off => light_grid[i][j] = false,
-                Operation::On => light_grid[i][j] = true,
-                Operation::Toggle => light_grid[i][j] = !light_grid[i][j],
+                Operation::Off => *light = false,
+                Operation::On => *light = true,
+                Operation::Toggle => *light = !*light,
             }
-            lights = lights + 1;
+            lights += 1;
         }
     }
 
     lights
 }
 
-fn apply_instruction_brightness(
-    instruction: &Instruction,
-    light_grid: &mut Vec<Vec<usize>>,
-) -> isize {
+fn apply_instruction_brightness(instruction: &Instruction, light_grid: &mut Grid<usize>) -> isize {
     let mut brightness = 0;
-    for i in instruction.range_from.0..(instruction.range_to.0 + 1) {
-        for j in instruction.range_from.1..(instruction.range_to.1 + 1) {
+    for x in instruction.range_from.0..=instruction.range_to.0 {
+        for y in instruction.range_from.1..=instruction.range_to.1 {
+            let light = &mut light_grid[(x, y)];
             match instruction.operation {
                 Operation::Off => {
-                    light_grid[i][j] = if light_grid[i][j] == 0 {
-                        0
-                    } else {
-                        light_grid[i][j] - 1
-                    };
-                    brightness = brightness - 1;
+                    *light = if *light == 0 { 0 } else { *light - 1 };
+                    brightness -= 1;
                 }
                 Operation::On => {
-                    light_grid[i][j] = light_grid[i][j] + 1;
-                    brightness = brightness + 1
+                    *light += 1;
+                    brightness += 1
                 }
                 Operation::Toggle => {
-                    light_grid[i][j] = light_grid[i][j] + 2;
-                    brightness = brightness + 2
+                    *light += 2;
+                    brightness += 2
                 }
             }
         }
@@ -130,31 +170,51 @@ mod tests {
 
     #[test]
     fn test_apply_instruction() {
-        let instruction1 = Instruction::new("0,0", "999,999", Operation::On);
-        let instruction2 = Instruction::new("0,0", "999,0", Operation::Toggle);
-        let instruction3 = Instruction::new("499,499", "500,500", Operation::Off);
+        let instructions = Problem
+            .parse_str(
+                "turn on 0,0 through 999,999\n\
+                 toggle 0,0 through 999,0\n\
+                 turn off 499,499 through 500,500\n",
+            )
+            .unwrap();
 
-        let mut light_grid = vec![vec![false; 1000]; 1000];
+        let mut light_grid = Grid::new(1000, 1000, false);
 
-        assert_eq!(apply_instruction(&instruction1, &mut light_grid), 1000000);
-        assert_eq!(apply_instruction(&instruction2, &mut light_grid), 1000);
-        assert_eq!(apply_instruction(&instruction3, &mut light_grid), 4);
+        assert_eq!(
+            apply_instruction(&instructions[0], &mut light_grid),
+            1000000
+        );
+        assert_eq!(apply_instruction(&instructions[1], &mut light_grid), 1000);
+        assert_eq!(apply_instruction(&instructions[2], &mut light_grid), 4);
     }
 
     #[test]
     fn test_apply_instruction_brightness() {
-        let instruction1 = Instruction::new("0,0", "0,0", Operation::On);
-        let instruction2 = Instruction::new("0,0", "999,999", Operation::Toggle);
+        let instructions = Problem
+            .parse_str("turn on 0,0 through 0,0\ntoggle 0,0 through 999,999\n")
+            .unwrap();
 
-        let mut light_grid = vec![vec![0; 1000]; 1000];
+        let mut light_grid = Grid::new(1000, 1000, 0);
 
         assert_eq!(
-            apply_instruction_brightness(&instruction1, &mut light_grid),
+            apply_instruction_brightness(&instructions[0], &mut light_grid),
             1
         );
         assert_eq!(
-            apply_instruction_brightness(&instruction2, &mut light_grid),
+            apply_instruction_brightness(&instructions[1], &mut light_grid),
             2000000
         );
+        assert_eq!(Problem.solve_part_two(&instructions).unwrap(), 2000001);
+    }
+
+    #[test]
+    fn test_parse_instruction_error() {
+        let error = Problem
+            .parse_str("turn on 0,0 through 999,999\nturn on 0,0 through 999,x\n")
+            .err()
+            .unwrap();
+
+        assert_eq!(error.line, Some(2));
+        assert_eq!(error.column, Some(25));
     }
 }
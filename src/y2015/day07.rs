@@ -1,17 +1,17 @@
+use crate::error::{field, parse_token, Error, Result};
+use crate::graph::Graph;
 use crate::solver::Solver;
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 enum Operation {
-    AND(String, String),
-    OR(String, String),
-    LSHIFT(String, u8),
-    RSHIFT(String, u8),
-    NOT(String),
-    ASSIGN(u16),
-    ASSIGNREF(String),
+    And(String, String),
+    Or(String, String),
+    Lshift(String, u8),
+    Rshift(String, u8),
+    Not(String),
+    Assign(u16),
+    AssignRef(String),
 }
 
 pub struct Instruction {
@@ -20,7 +20,7 @@ pub struct Instruction {
 }
 
 impl Instruction {
-    fn new(line: &str) -> Instruction {
+    fn parse(line_number: usize, line: &str) -> Result<Instruction> {
         let line_split: Vec<&str> = line.split(' ').collect();
         let operation: Operation;
         let wire_output: String;
@@ -28,163 +28,167 @@ impl Instruction {
             let num = line_split[0].parse::<u16>();
             match num {
                 Ok(val) => {
-                    operation = Operation::ASSIGN(val);
+                    operation = Operation::Assign(val);
                     wire_output = line_split[2].to_string()
                 }
                 Err(_err) => {
-                    operation = Operation::ASSIGNREF(line_split[0].to_string());
+                    operation = Operation::AssignRef(line_split[0].to_string());
                     wire_output = line_split[2].to_string();
                 }
             }
         } else if line_split.len() == 4 {
-            operation = Operation::NOT(line_split[1].to_string());
+            operation = Operation::Not(line_split[1].to_string());
             wire_output = line_split[3].to_string();
         } else {
-            wire_output = line_split[4].to_string();
+            wire_output = field(line_number, line, &line_split, 4)?.to_string();
             match line_split[1] {
                 "AND" => {
-                    operation = Operation::AND(line_split[0].to_string(), line_split[2].to_string())
+                    operation = Operation::And(line_split[0].to_string(), line_split[2].to_string())
                 }
                 "OR" => {
-                    operation = Operation::OR(line_split[0].to_string(), line_split[2].to_string())
+                    operation = Operation::Or(line_split[0].to_string(), line_split[2].to_string())
                 }
                 "LSHIFT" => {
-                    operation = Operation::LSHIFT(
+                    operation = Operation::Lshift(
                         line_split[0].to_string(),
-                        line_split[2].to_string().parse::<u8>().unwrap(),
+                        parse_token(line_number, line, line_split[2])?,
                     )
                 }
                 "RSHIFT" => {
-                    operation = Operation::RSHIFT(
+                    operation = Operation::Rshift(
                         line_split[0].to_string(),
-                        line_split[2].to_string().parse::<u8>().unwrap(),
+                        parse_token(line_number, line, line_split[2])?,
                     )
                 }
-                _ => panic!("Unexpected input"),
+                other => {
+                    return Err(Error::at_token(
+                        line_number,
+                        line,
+                        other,
+                        format!("unknown gate '{}'", other),
+                    ))
+                }
             }
         }
 
-        Instruction {
-            operation: operation,
-            wire_output: wire_output,
-        }
+        Ok(Instruction {
+            operation,
+            wire_output,
+        })
     }
 }
 
 pub struct Problem;
 
+register_solver!(2015, 7, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Instruction>;
     type Output1 = u16;
     type Output2 = u16;
 
-    fn parse_input(&self, file: File) -> Vec<Instruction> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Instruction>> {
+        reader
             .lines()
-            .map(|l| {
-                let l = l.unwrap();
-                Instruction::new(&l)
-            })
+            .enumerate()
+            .map(|(i, l)| Instruction::parse(i + 1, &l?))
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<Instruction>) -> u16 {
-        let mut wire_map: HashMap<String, u16> = HashMap::new();
-        find_wire_signal(&input, &mut wire_map, "a")
+    fn solve_part_one(&self, input: &Vec<Instruction>) -> Result<u16> {
+        find_wire_signal(input, &HashMap::new(), "a")
     }
 
-    fn solve_part_two(&self, input: &Vec<Instruction>) -> u16 {
-        let signal_a = self.solve_part_one(input);
+    fn solve_part_two(&self, input: &Vec<Instruction>) -> Result<u16> {
+        let signal_a = self.solve_part_one(input)?;
         let mut wire_map: HashMap<String, u16> = HashMap::new();
         wire_map.insert("b".to_string(), signal_a);
-        find_wire_signal(&input, &mut wire_map, "a")
+        find_wire_signal(input, &wire_map, "a")
     }
 }
 
+impl Operation {
+    // The operands of the gate, which are either wires or signals
+    fn inputs(&self) -> Vec<&str> {
+        match self {
+            Operation::And(input1, input2) | Operation::Or(input1, input2) => {
+                vec![input1, input2]
+            }
+            Operation::Lshift(input, _)
+            | Operation::Rshift(input, _)
+            | Operation::Not(input)
+            | Operation::AssignRef(input) => vec![input],
+            Operation::Assign(_) => Vec::new(),
+        }
+    }
+}
+
+// The signal on `wire`, where the wires in `wire_map` carry the given signal
+// whatever is connected to them
 fn find_wire_signal(
     instructions: &Vec<Instruction>,
-    wire_map: &mut HashMap<String, u16>,
+    wire_map: &HashMap<String, u16>,
     wire: &str,
-) -> u16 {
-    let instruction_for_wire = instructions
-        .iter()
-        .find(|i| i.wire_output == *wire)
-        .unwrap();
-    let wire_output = &instruction_for_wire.wire_output;
-    match &instruction_for_wire.operation {
-        Operation::ASSIGN(value) => {
-            if !wire_map.contains_key(wire_output) {
-                wire_map.insert(wire_output.to_string(), *value);
-            }
-        }
-        Operation::ASSIGNREF(input) => {
-            let output;
-            if wire_map.contains_key(input) {
-                output = wire_map[input];
-            } else {
-                output = find_wire_signal(&instructions, wire_map, input);
-            }
-            wire_map.insert(wire_output.to_string(), output);
-        }
-        Operation::AND(input1, input2) => {
-            let output1;
-            let output2;
-            if wire_map.contains_key(input1) && wire_map.contains_key(input2) {
-                output1 = wire_map[input1];
-                output2 = wire_map[input2];
-            } else {
-                output1 = if input1 == "1" {
-                    1
-                } else {
-                    find_wire_signal(&instructions, wire_map, input1)
-                };
-                output2 = find_wire_signal(&instructions, wire_map, input2);
-            }
-            wire_map.insert(wire_output.to_string(), output1 & output2);
-        }
-        Operation::OR(input1, input2) => {
-            let output1;
-            let output2;
-            if wire_map.contains_key(input1) && wire_map.contains_key(input2) {
-                output1 = wire_map[input1];
-                output2 = wire_map[input2];
-            } else {
-                output1 = find_wire_signal(&instructions, wire_map, input1);
-                output2 = find_wire_signal(&instructions, wire_map, input2);
-            }
-            wire_map.insert(wire_output.to_string(), output1 | output2);
+) -> Result<u16> {
+    // Every wire with an edge to the wires its signal is computed from
+    let mut circuit = Graph::new();
+    let mut operations = HashMap::new();
+    for instruction in instructions {
+        let output = instruction.wire_output.as_str();
+        circuit.add_node(output);
+        operations.insert(output, &instruction.operation);
+        if wire_map.contains_key(output) {
+            continue;
         }
-        Operation::LSHIFT(input1, input2) => {
-            let output1;
-            if wire_map.contains_key(input1) {
-                output1 = wire_map[input1];
-            } else {
-                output1 = find_wire_signal(&instructions, wire_map, input1);
-            }
-            wire_map.insert(wire_output.to_string(), output1 << input2);
-        }
-        Operation::RSHIFT(input1, input2) => {
-            let output1;
-            if wire_map.contains_key(input1) {
-                output1 = wire_map[input1];
-            } else {
-                output1 = find_wire_signal(&instructions, wire_map, input1);
+
+        for input in instruction.operation.inputs() {
+            if input.parse::<u16>().is_err() {
+                circuit.add_edge(output, input, ());
             }
-            wire_map.insert(wire_output.to_string(), output1 >> input2);
         }
-        Operation::NOT(input) => {
-            let output;
-            if wire_map.contains_key(input) {
-                output = wire_map[input];
-            } else {
-                output = find_wire_signal(&instructions, wire_map, input);
+    }
+
+    let no_signal = |wire: &str| Error::solve(format!("no signal is provided to wire '{}'", wire));
+    let start = circuit.index(wire).ok_or_else(|| no_signal(wire))?;
+    let distances = circuit.bfs(start);
+    if let Some(unconnected) = (0..circuit.len())
+        .filter(|&i| distances[i].is_some())
+        .map(|i| *circuit.node(i))
+        .find(|wire| !operations.contains_key(wire) && !wire_map.contains_key(*wire))
+    {
+        return Err(no_signal(unconnected));
+    }
+
+    circuit
+        .fold(start, |node, inputs| {
+            let wire = *circuit.node(node);
+            if let Some(signal) = wire_map.get(wire) {
+                return *signal;
             }
-            wire_map.insert(wire_output.to_string(), !output);
-        }
-    };
 
-    wire_map[wire_output]
+            let signals: HashMap<&str, u16> = circuit
+                .edges(node)
+                .iter()
+                .map(|(to, _)| *circuit.node(*to))
+                .zip(inputs.iter().map(|(_, signal)| **signal))
+                .collect();
+            let signal = |input: &String| input.parse().unwrap_or_else(|_| signals[input.as_str()]);
+            match operations[wire] {
+                Operation::Assign(value) => *value,
+                Operation::AssignRef(input) => signal(input),
+                Operation::And(input1, input2) => signal(input1) & signal(input2),
+                Operation::Or(input1, input2) => signal(input1) | signal(input2),
+                Operation::Lshift(input1, input2) => signal(input1) << input2,
+                Operation::Rshift(input1, input2) => signal(input1) >> input2,
+                Operation::Not(input) => !signal(input),
+            }
+        })
+        .map_err(|wire| {
+            Error::solve(format!(
+                "the signal on wire '{}' depends on itself",
+                circuit.node(wire)
+            ))
+        })
 }
 
 #[cfg(test)]
@@ -193,26 +197,49 @@ mod tests {
 
     #[test]
     fn test_find_wire_signal() {
-        let instructions = vec![
-            Instruction::new("x AND y -> d"),
-            Instruction::new("x OR y -> e"),
-            Instruction::new("x LSHIFT 2 -> f"),
-            Instruction::new("y RSHIFT 2 -> g"),
-            Instruction::new("NOT x -> h"),
-            Instruction::new("NOT y -> i"),
-            Instruction::new("123 -> x"),
-            Instruction::new("456 -> y"),
-        ];
+        let instructions = Problem
+            .parse_str(
+                "123 -> x\n\
+                 456 -> y\n\
+                 x AND y -> d\n\
+                 x OR y -> e\n\
+                 x LSHIFT 2 -> f\n\
+                 y RSHIFT 2 -> g\n\
+                 NOT x -> h\n\
+                 NOT y -> i\n",
+            )
+            .unwrap();
 
-        let mut wire_map: HashMap<String, u16> = HashMap::new();
+        let wire_map: HashMap<String, u16> = HashMap::new();
 
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "d"), 72);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "e"), 507);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "f"), 492);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "g"), 114);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "h"), 65412);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "i"), 65079);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "x"), 123);
-        assert_eq!(find_wire_signal(&instructions, &mut wire_map, "y"), 456);
+        assert_eq!(find_wire_signal(&instructions, &wire_map, "d").unwrap(), 72);
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "e").unwrap(),
+            507
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "f").unwrap(),
+            492
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "g").unwrap(),
+            114
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "h").unwrap(),
+            65412
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "i").unwrap(),
+            65079
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "x").unwrap(),
+            123
+        );
+        assert_eq!(
+            find_wire_signal(&instructions, &wire_map, "y").unwrap(),
+            456
+        );
     }
 }
@@ -1,79 +1,122 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 8, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
+                if line.len() < 2 || !line.starts_with('"') || !line.ends_with('"') {
+                    return Err(Error::parse_at(i + 1, 1, "expected a quoted string"));
+                }
+                if let Some(column) = find_invalid_escape(&line) {
+                    return Err(Error::parse_at(
+                        i + 1,
+                        column,
+                        "expected \\\\, \\\" or \\x and two hex digits",
+                    ));
+                }
+                Ok(line)
+            })
+            .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        input
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input
             .iter()
-            .map(|i| code_length(&i) - string_length(&i))
-            .sum()
+            .map(|i| code_length(i) - string_length(i))
+            .sum())
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
-        input
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input
             .iter()
-            .map(|i| encoded_length(&i) - code_length(&i))
-            .sum()
+            .map(|i| encoded_length(i) - code_length(i))
+            .sum())
+    }
+}
+
+// The column of the first escape sequence inside the quotes of `line` that is
+// not `\\`, `\"` or `\x` with two hex digits
+fn find_invalid_escape(line: &str) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut index = 1;
+    while index < chars.len() - 1 {
+        if chars[index] == '\\' {
+            let length = match chars[index + 1] {
+                '\\' | '"' if index + 1 < chars.len() - 1 => 2,
+                'x' if index + 3 < chars.len() - 1
+                    && chars[index + 2..index + 4]
+                        .iter()
+                        .all(|c| c.is_ascii_hexdigit()) =>
+                {
+                    4
+                }
+                _ => return Some(index + 1),
+            };
+            index += length;
+        } else {
+            index += 1;
+        }
     }
+    None
 }
 
-fn string_length(line: &String) -> usize {
-    let mut length = code_length(&line);
-    length = length - 2; // starting and trailing double-quotes
+fn string_length(line: &str) -> usize {
+    let mut length = code_length(line);
+    length -= 2; // starting and trailing double-quotes
 
+    let chars: Vec<char> = line.chars().collect();
     let mut index = 1;
-    while index < line.chars().count() - 1 {
-        let c = line.chars().nth(index).unwrap();
-        if c == '\\' {
-            if line.chars().nth(index + 1).unwrap() == 'x' {
-                length = length - 3;
-                index = index + 4;
+    while index < chars.len() - 1 {
+        if chars[index] == '\\' {
+            if chars[index + 1] == 'x' {
+                length -= 3;
+                index += 4;
             } else {
-                length = length - 1;
-                index = index + 2;
+                length -= 1;
+                index += 2;
             }
         } else {
-            index = index + 1;
+            index += 1;
         }
     }
 
     length
 }
 
-fn code_length(line: &String) -> usize {
+fn code_length(line: &str) -> usize {
     line.chars().count()
 }
 
-fn encoded_length(line: &String) -> usize {
-    let mut length = code_length(&line);
-    length = length + 4; // starting and trailing double-quotes
+fn encoded_length(line: &str) -> usize {
+    let mut length = code_length(line);
+    length += 4; // starting and trailing double-quotes
 
+    let chars: Vec<char> = line.chars().collect();
     let mut index = 1;
-    while index < line.chars().count() - 1 {
-        let c = line.chars().nth(index).unwrap();
-        if c == '\\' {
-            if line.chars().nth(index + 1).unwrap() == 'x' {
-                length = length + 1;
-                index = index + 4;
+    while index < chars.len() - 1 {
+        if chars[index] == '\\' {
+            if chars[index + 1] == 'x' {
+                length += 1;
+                index += 4;
             } else {
-                length = length + 2;
-                index = index + 2;
+                length += 2;
+                index += 2;
             }
         } else {
-            index = index + 1;
+            index += 1;
         }
     }
 
@@ -84,42 +127,45 @@ fn encoded_length(line: &String) -> usize {
 mod tests {
     use crate::y2015::day08::*;
 
+    const EXAMPLE: &str = "\"\"\n\"abc\"\n\"aaa\\\"aaa\"\n\"\\x27\"\n";
+
     #[test]
     fn test_string_length() {
-        let ex1 = "\"\"";
-        let ex2 = "\"abc\"";
-        let ex3 = "\"aaa\\\"aaa\"";
-        let ex4 = "\"\\x27\"";
-
-        assert_eq!(string_length(&ex1.to_string()), 0);
-        assert_eq!(string_length(&ex2.to_string()), 3);
-        assert_eq!(string_length(&ex3.to_string()), 7);
-        assert_eq!(string_length(&ex4.to_string()), 1);
+        let lines = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(string_length(&lines[0]), 0);
+        assert_eq!(string_length(&lines[1]), 3);
+        assert_eq!(string_length(&lines[2]), 7);
+        assert_eq!(string_length(&lines[3]), 1);
     }
 
     #[test]
     fn test_code_length() {
-        let ex1 = "\"\"";
-        let ex2 = "\"abc\"";
-        let ex3 = "\"aaa\\\"aaa\"";
-        let ex4 = "\"\\x27\"";
-
-        assert_eq!(code_length(&ex1.to_string()), 2);
-        assert_eq!(code_length(&ex2.to_string()), 5);
-        assert_eq!(code_length(&ex3.to_string()), 10);
-        assert_eq!(code_length(&ex4.to_string()), 6);
+        let lines = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(code_length(&lines[0]), 2);
+        assert_eq!(code_length(&lines[1]), 5);
+        assert_eq!(code_length(&lines[2]), 10);
+        assert_eq!(code_length(&lines[3]), 6);
+        assert_eq!(Problem.solve_part_one(&lines).unwrap(), 12);
     }
 
     #[test]
     fn test_encoded_length() {
-        let ex1 = "\"\"";
-        let ex2 = "\"abc\"";
-        let ex3 = "\"aaa\\\"aaa\"";
-        let ex4 = "\"\\x27\"";
-
-        assert_eq!(encoded_length(&ex1.to_string()), 6);
-        assert_eq!(encoded_length(&ex2.to_string()), 9);
-        assert_eq!(encoded_length(&ex3.to_string()), 16);
-        assert_eq!(encoded_length(&ex4.to_string()), 11);
+        let lines = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(encoded_length(&lines[0]), 6);
+        assert_eq!(encoded_length(&lines[1]), 9);
+        assert_eq!(encoded_length(&lines[2]), 16);
+        assert_eq!(encoded_length(&lines[3]), 11);
+        assert_eq!(Problem.solve_part_two(&lines).unwrap(), 19);
+    }
+
+    #[test]
+    fn test_parse_invalid_escape() {
+        assert!(Problem.parse_str("\"\\\"\n").is_err());
+        assert!(Problem.parse_str("\"\\x2\"\n").is_err());
+        assert!(Problem.parse_str("\"\\q\"\n").is_err());
+        assert!(Problem.parse_str("\"\\\\\"\n").is_ok());
     }
 }
@@ -1,136 +1,76 @@
+use crate::error::{field, parse_field, Error, Result};
+use crate::graph::{Goal, Graph, Tour};
 use crate::solver::Solver;
-use itertools::Itertools;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, collections::HashSet, fs::File};
+
+// Every location with an edge both ways for each distance
+pub type Routes = Graph<String, usize>;
 
 pub struct Problem;
 
+register_solver!(2015, 9, Problem);
+
 impl Solver for Problem {
-    type Input = (HashSet<String>, HashMap<String, usize>);
+    type Input = Routes;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> (HashSet<String>, HashMap<String, usize>) {
-        let mut locations = HashSet::new();
-        let mut routes = HashMap::new();
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().for_each(|l| {
-            let line = l.unwrap();
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Routes> {
+        let mut routes = Graph::new();
+        for (i, l) in reader.lines().enumerate() {
+            let line = l?;
             let line_split: Vec<&str> = line.split(' ').collect();
-            let from_location = line_split[0];
-            let to_location = line_split[2];
-            let distance = line_split[4].parse::<usize>().unwrap();
-
-            locations.insert(from_location.to_string());
-            locations.insert(to_location.to_string());
-            routes.insert(
-                format!("{}{}", from_location.to_string(), to_location.to_string()),
-                distance,
-            );
-            routes.insert(
-                format!("{}{}", to_location.to_string(), from_location.to_string()),
-                distance,
-            );
-        });
-
-        (locations, routes)
-    }
+            let from_location = field(i + 1, &line, &line_split, 0)?;
+            let to_location = field(i + 1, &line, &line_split, 2)?;
+            let distance: usize = parse_field(i + 1, &line, &line_split, 4)?;
 
-    fn solve_part_one(&self, input: &(HashSet<String>, HashMap<String, usize>)) -> usize {
-        fastest_routes(&input)
-    }
+            routes.add_edge(from_location.to_string(), to_location.to_string(), distance);
+            routes.add_edge(to_location.to_string(), from_location.to_string(), distance);
+        }
 
-    fn solve_part_two(&self, input: &(HashSet<String>, HashMap<String, usize>)) -> usize {
-        longest_routes(&input)
+        Ok(routes)
     }
-}
 
-fn fastest_routes(input: &(HashSet<String>, HashMap<String, usize>)) -> usize {
-    let mut fastest_route = usize::MAX;
-    let combinations = input.0.iter().permutations(input.0.len());
-
-    for combination in combinations {
-        let mut current_route = 0;
-        for (i, _location) in combination.iter().enumerate() {
-            if i == combination.len() - 1 {
-                break;
-            }
-
-            current_route =
-                current_route + input.1[&format!("{}{}", combination[i], combination[i + 1])];
-        }
-
-        if current_route < fastest_route {
-            fastest_route = current_route;
-        }
+    fn solve_part_one(&self, input: &Routes) -> Result<usize> {
+        fastest_routes(input)
     }
 
-    fastest_route
+    fn solve_part_two(&self, input: &Routes) -> Result<usize> {
+        longest_routes(input)
+    }
 }
 
-fn longest_routes(input: &(HashSet<String>, HashMap<String, usize>)) -> usize {
-    let mut longest_route = 0;
-    let combinations = input.0.iter().permutations(input.0.len());
-
-    for combination in combinations {
-        let mut current_route = 0;
-        for (i, _location) in combination.iter().enumerate() {
-            if i == combination.len() - 1 {
-                break;
-            }
-
-            current_route =
-                current_route + input.1[&format!("{}{}", combination[i], combination[i + 1])];
-        }
-
-        if current_route > longest_route {
-            longest_route = current_route;
-        }
-    }
+fn fastest_routes(routes: &Routes) -> Result<usize> {
+    routes
+        .held_karp(Tour::Path, Goal::Shortest)?
+        .ok_or_else(|| Error::solve("no route visits every location"))
+}
 
-    longest_route
+fn longest_routes(routes: &Routes) -> Result<usize> {
+    routes
+        .held_karp(Tour::Path, Goal::Longest)?
+        .ok_or_else(|| Error::solve("no route visits every location"))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::y2015::day09::*;
 
+    const EXAMPLE: &str = "London to Dublin = 464\n\
+                           London to Belfast = 518\n\
+                           Dublin to Belfast = 141\n";
+
     #[test]
     fn test_fastest_routes() {
-        let mut locations = HashSet::new();
-        let mut routes: HashMap<String, usize> = HashMap::new();
-
-        locations.insert("London".to_string());
-        locations.insert("Dublin".to_string());
-        locations.insert("Belfast".to_string());
+        let input = Problem.parse_str(EXAMPLE).unwrap();
 
-        routes.insert("LondonDublin".to_string(), 464);
-        routes.insert("DublinLondon".to_string(), 464);
-        routes.insert("LondonBelfast".to_string(), 518);
-        routes.insert("BelfastLondon".to_string(), 518);
-        routes.insert("DublinBelfast".to_string(), 141);
-        routes.insert("BelfastDublin".to_string(), 141);
-
-        assert_eq!(fastest_routes(&(locations, routes)), 605);
+        assert_eq!(fastest_routes(&input).unwrap(), 605);
     }
 
     #[test]
     fn test_longest_routes() {
-        let mut locations = HashSet::new();
-        let mut routes: HashMap<String, usize> = HashMap::new();
-
-        locations.insert("London".to_string());
-        locations.insert("Dublin".to_string());
-        locations.insert("Belfast".to_string());
-
-        routes.insert("LondonDublin".to_string(), 464);
-        routes.insert("DublinLondon".to_string(), 464);
-        routes.insert("LondonBelfast".to_string(), 518);
-        routes.insert("BelfastLondon".to_string(), 518);
-        routes.insert("DublinBelfast".to_string(), 141);
-        routes.insert("BelfastDublin".to_string(), 141);
+        let input = Problem.parse_str(EXAMPLE).unwrap();
 
-        assert_eq!(longest_routes(&(locations, routes)), 982);
+        assert_eq!(longest_routes(&input).unwrap(), 982);
     }
 }
@@ -1,36 +1,47 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{fs::File, str::Chars};
+use std::str::Chars;
 
 pub struct Problem;
 
+register_solver!(2015, 10, Problem);
+
 impl Solver for Problem {
     type Input = String;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> String {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().nth(0).unwrap().unwrap()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<String> {
+        let line = reader
+            .lines()
+            .next()
+            .ok_or_else(|| Error::parse("input is empty"))??;
+        if line.is_empty() {
+            return Err(Error::parse_at(1, 1, "expected a sequence of digits"));
+        }
+        match line.find(|c: char| !c.is_ascii_digit()) {
+            Some(index) => Err(Error::parse_at(1, index + 1, "expected only digits")),
+            None => Ok(line),
+        }
     }
 
-    fn solve_part_one(&self, input: &String) -> usize {
+    fn solve_part_one(&self, input: &String) -> Result<usize> {
         let mut output = input.to_string();
         for _ in 0..40 {
             output = look_and_say(&output);
         }
 
-        output.len()
+        Ok(output.len())
     }
 
-    fn solve_part_two(&self, input: &String) -> usize {
+    fn solve_part_two(&self, input: &String) -> Result<usize> {
         let mut output = input.to_string();
         for _ in 0..50 {
             output = look_and_say(&output);
         }
 
-        output.len()
+        Ok(output.len())
     }
 }
 
@@ -38,7 +49,10 @@ fn look_and_say(input: &str) -> String {
     let mut new_string = String::new();
 
     let mut chars = input.chars();
-    let mut c = chars.next().unwrap();
+    let mut c = match chars.next() {
+        Some(c) => c,
+        None => return new_string,
+    };
     loop {
         let next = look_and_say_char(c, &mut new_string, &mut chars);
         match next {
@@ -56,7 +70,7 @@ fn look_and_say_char(c: char, new_string: &mut String, chars: &mut Chars) -> Opt
         match look_ahead_next {
             Some(look_ahead) => {
                 if look_ahead == c {
-                    count = count + 1;
+                    count += 1;
                 } else {
                     new_string.push_str(&format!("{}{}", count, c));
                     return look_ahead_next;
@@ -76,10 +90,13 @@ mod tests {
 
     #[test]
     fn test_look_and_say() {
-        assert_eq!(look_and_say("1"), "11");
+        let input = Problem.parse_str("1\n").unwrap();
+
+        assert_eq!(look_and_say(&input), "11");
         assert_eq!(look_and_say("11"), "21");
         assert_eq!(look_and_say("21"), "1211");
         assert_eq!(look_and_say("1211"), "111221");
         assert_eq!(look_and_say("111221"), "312211");
+        assert_eq!(look_and_say(""), "");
     }
 }
@@ -1,52 +1,70 @@
+use crate::cancel;
+use crate::error::{Error, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2015, 11, Problem);
+
 impl Solver for Problem {
     type Input = String;
     type Output1 = String;
     type Output2 = String;
 
-    fn parse_input(&self, file: File) -> String {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().nth(0).unwrap().unwrap()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<String> {
+        let line = reader
+            .lines()
+            .next()
+            .ok_or_else(|| Error::parse("input is empty"))??;
+        if line.is_empty() {
+            return Err(Error::parse_at(1, 1, "expected a password"));
+        }
+        match line.find(|c: char| !c.is_ascii_lowercase()) {
+            Some(index) => Err(Error::parse_at(
+                1,
+                index + 1,
+                "expected only lowercase letters",
+            )),
+            None => Ok(line),
+        }
     }
 
-    fn solve_part_one(&self, input: &String) -> String {
+    fn solve_part_one(&self, input: &String) -> Result<String> {
         get_next_password(input)
     }
 
-    fn solve_part_two(&self, input: &String) -> String {
-        get_next_password(&get_next_password(input)[..])
+    fn solve_part_two(&self, input: &String) -> Result<String> {
+        get_next_password(&get_next_password(input)?)
     }
 }
 
-fn get_next_password(password: &str) -> String {
+fn get_next_password(password: &str) -> Result<String> {
     let mut bytes = password.to_string().into_bytes();
     let mut index = bytes.len() - 1;
 
     loop {
+        cancel::check()?;
         let c = bytes[index];
 
         if c == 104 || c == 107 || c == 110 {
-            bytes[index] = bytes[index] + 2;
+            bytes[index] += 2;
         } else {
-            bytes[index] = bytes[index] + 1;
+            bytes[index] += 1;
         }
 
         if bytes[index] > 122 {
             bytes[index] = 97;
-            index = index - 1;
+            index = index
+                .checked_sub(1)
+                .ok_or_else(|| Error::solve(format!("no password follows {}", password)))?;
             continue;
         }
 
-        let password: String = bytes.iter().map(|b| *b as char).into_iter().collect();
+        let password: String = bytes.iter().map(|b| *b as char).collect();
 
         if is_password_valid(&password) {
-            return password;
+            return Ok(password);
         } else {
             index = bytes.len() - 1;
             continue;
@@ -73,7 +91,7 @@ fn is_password_valid(password: &str) -> bool {
         let c1 = bytes[i + 1];
 
         if pairs < 2 && c == &c1 && (i == 0 || c != &bytes[i - 1]) {
-            pairs = pairs + 1;
+            pairs += 1;
         }
 
         if i >= password.len() - 2 {
@@ -94,16 +112,25 @@ mod tests {
 
     #[test]
     fn test_is_password_valid() {
-        assert_eq!(is_password_valid("hijklmmn"), false);
-        assert_eq!(is_password_valid("abbceffg"), false);
-        assert_eq!(is_password_valid("abbcegjk"), false);
-        assert_eq!(is_password_valid("abcdffaa"), true);
-        assert_eq!(is_password_valid("ghjaabcc"), true);
+        assert!(!is_password_valid("hijklmmn"));
+        assert!(!is_password_valid("abbceffg"));
+        assert!(!is_password_valid("abbcegjk"));
+        assert!(is_password_valid("abcdffaa"));
+        assert!(is_password_valid("ghjaabcc"));
     }
 
     #[test]
     fn test_get_next_password() {
-        assert_eq!(get_next_password("abcdefgh"), "abcdffaa");
-        assert_eq!(get_next_password("ghijklmn"), "ghjaabcc");
+        let ex1 = Problem.parse_str("abcdefgh\n").unwrap();
+        let ex2 = Problem.parse_str("ghijklmn\n").unwrap();
+
+        assert_eq!(get_next_password(&ex1).unwrap(), "abcdffaa");
+        assert_eq!(get_next_password(&ex2).unwrap(), "ghjaabcc");
+    }
+
+    #[test]
+    fn test_get_next_password_without_successor() {
+        assert!(get_next_password("x").is_err());
+        assert!(get_next_password("zzz").is_err());
     }
 }
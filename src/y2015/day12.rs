@@ -1,84 +1,80 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{fs::File, str::Bytes};
+use std::str::Bytes;
 
 pub struct Problem;
 
+register_solver!(2015, 12, Problem);
+
 impl Solver for Problem {
     type Input = String;
     type Output1 = isize;
     type Output2 = isize;
 
-    fn parse_input(&self, file: File) -> String {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().nth(0).unwrap().unwrap()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<String> {
+        Ok(reader
+            .lines()
+            .next()
+            .ok_or_else(|| Error::parse("input is empty"))??)
     }
 
-    fn solve_part_one(&self, input: &String) -> isize {
-        add_numbers(&input)
+    fn solve_part_one(&self, input: &String) -> Result<isize> {
+        add_numbers(input)
     }
 
-    fn solve_part_two(&self, input: &String) -> isize {
-        add_numbers_check_red(&input)
+    fn solve_part_two(&self, input: &String) -> Result<isize> {
+        add_numbers_check_red(input)
     }
 }
 
-fn add_numbers(json: &String) -> isize {
+fn add_numbers(json: &str) -> Result<isize> {
     let mut sum = 0;
 
     let mut bytes = json.bytes();
-    loop {
-        match bytes.next() {
-            Some(b) => {
-                if b == b'-' {
-                    sum = sum - get_number(&mut bytes, &mut String::new()).0;
-                }
-
-                if b >= b'1' && b <= b'9' {
-                    sum = sum + get_number(&mut bytes, &mut String::from(b as char)).0;
-                }
-            }
-            None => break,
+    while let Some(b) = bytes.next() {
+        if b == b'-' {
+            sum -= get_number(&mut bytes, &mut String::new())?.0;
+        }
+
+        if (b'1'..=b'9').contains(&b) {
+            sum += get_number(&mut bytes, &mut String::from(b as char))?.0;
         }
     }
 
-    sum
+    Ok(sum)
 }
 
-fn add_numbers_check_red(json: &String) -> isize {
+fn add_numbers_check_red(json: &str) -> Result<isize> {
     let mut sum = 0;
 
     let mut bytes = json.bytes();
-    loop {
-        match bytes.next() {
-            Some(b) => {
-                // new object
-                if b == b'{' {
-                    sum = sum + get_object_value(&mut bytes);
-                }
-
-                if b == b'-' {
-                    sum = sum - get_number(&mut bytes, &mut String::new()).0;
-                }
-
-                if b >= b'1' && b <= b'9' {
-                    sum = sum + get_number(&mut bytes, &mut String::from(b as char)).0;
-                }
-            }
-            None => break,
+    while let Some(b) = bytes.next() {
+        // new object
+        if b == b'{' {
+            sum += get_object_value(&mut bytes)?;
+        }
+
+        if b == b'-' {
+            sum -= get_number(&mut bytes, &mut String::new())?.0;
+        }
+
+        if (b'1'..=b'9').contains(&b) {
+            sum += get_number(&mut bytes, &mut String::from(b as char))?.0;
         }
     }
 
-    sum
+    Ok(sum)
 }
 
-fn get_object_value(bytes: &mut Bytes) -> isize {
+fn get_object_value(bytes: &mut Bytes) -> Result<isize> {
     let mut sum = 0;
     let mut red = false;
     let mut object_string = String::new();
     loop {
-        let b = bytes.next().unwrap();
+        let b = bytes
+            .next()
+            .ok_or_else(|| Error::parse("unterminated object"))?;
         object_string.push(b as char);
         // check red
         if object_string.contains(":\"red\"") {
@@ -87,37 +83,37 @@ fn get_object_value(bytes: &mut Bytes) -> isize {
 
         // new object
         if b == b'{' {
-            sum = sum + get_object_value(&mut *bytes);
+            sum += get_object_value(&mut *bytes)?;
         }
         // end object
         else if b == b'}' {
             break;
         } else if b == b'-' {
-            let number = get_number(&mut *bytes, &mut String::new());
-            sum = sum - number.0;
+            let number = get_number(&mut *bytes, &mut String::new())?;
+            sum -= number.0;
             if number.1 {
                 break;
             }
-        } else if b >= b'1' && b <= b'9' {
-            let number = get_number(&mut *bytes, &mut String::from(b as char));
-            sum = sum + number.0;
+        } else if (b'1'..=b'9').contains(&b) {
+            let number = get_number(&mut *bytes, &mut String::from(b as char))?;
+            sum += number.0;
             if number.1 {
                 break;
             }
         }
     }
     if red {
-        0
+        Ok(0)
     } else {
-        sum
+        Ok(sum)
     }
 }
 
-fn get_number(bytes: &mut Bytes, number_string: &mut String) -> (isize, bool) {
+fn get_number(bytes: &mut Bytes, number_string: &mut String) -> Result<(isize, bool)> {
     let mut end_of_object = false;
-    loop {
-        let next = bytes.next().unwrap();
-        if next < b'0' || next > b'9' {
+    // a number may run up to the end of the input, as in a bare `1`
+    for next in bytes.by_ref() {
+        if !next.is_ascii_digit() {
             if next == b'}' {
                 end_of_object = true;
             }
@@ -125,42 +121,62 @@ fn get_number(bytes: &mut Bytes, number_string: &mut String) -> (isize, bool) {
         }
         number_string.push(next as char);
     }
-    (number_string.parse::<isize>().unwrap(), end_of_object)
+    let number = number_string
+        .parse::<isize>()
+        .map_err(|e| Error::parse(format!("invalid number '{}': {}", number_string, e)))?;
+    Ok((number, end_of_object))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::y2015::day12::*;
 
+    fn parse(json: &str) -> String {
+        Problem.parse_str(json).unwrap()
+    }
+
     #[test]
     fn test_add_numbers() {
-        assert_eq!(add_numbers(&"[1,2,3]".to_string()), 6);
-        assert_eq!(add_numbers(&"{\"a\":2,\"b\":4}".to_string()), 6);
-        assert_eq!(add_numbers(&"[[[3]]]".to_string()), 3);
-        assert_eq!(add_numbers(&"{\"a\":{\"b\":4},\"c\":-1}".to_string()), 3);
-        assert_eq!(add_numbers(&"{\"a\":[-1,1]}".to_string()), 0);
-        assert_eq!(add_numbers(&"[-1,{\"a\":1}]".to_string()), 0);
-        assert_eq!(add_numbers(&"[]".to_string()), 0);
-        assert_eq!(add_numbers(&"{}".to_string()), 0);
+        assert_eq!(add_numbers(&parse("[1,2,3]")).unwrap(), 6);
+        assert_eq!(add_numbers(&parse("{\"a\":2,\"b\":4}")).unwrap(), 6);
+        assert_eq!(add_numbers(&parse("[[[3]]]")).unwrap(), 3);
+        assert_eq!(
+            add_numbers(&parse("{\"a\":{\"b\":4},\"c\":-1}")).unwrap(),
+            3
+        );
+        assert_eq!(add_numbers(&parse("{\"a\":[-1,1]}")).unwrap(), 0);
+        assert_eq!(add_numbers(&parse("[-1,{\"a\":1}]")).unwrap(), 0);
+        assert_eq!(add_numbers(&parse("[]")).unwrap(), 0);
+        assert_eq!(add_numbers(&parse("{}")).unwrap(), 0);
     }
 
     #[test]
     fn test_add_numbers_check_red() {
-        assert_eq!(add_numbers_check_red(&"[1,2,3]".to_string()), 6);
+        assert_eq!(add_numbers_check_red(&parse("[1,2,3]")).unwrap(), 6);
         assert_eq!(
-            add_numbers_check_red(&"[1,{\"c\":\"red\",\"b\":2},3]".to_string()),
+            add_numbers_check_red(&parse("[1,{\"c\":\"red\",\"b\":2},3]")).unwrap(),
             4
         );
         assert_eq!(
-            add_numbers_check_red(&"{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5}".to_string()),
+            add_numbers_check_red(&parse("{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5}")).unwrap(),
             0
         );
-        assert_eq!(add_numbers_check_red(&"[1,\"red\",5]".to_string()), 6);
+        assert_eq!(add_numbers_check_red(&parse("[1,\"red\",5]")).unwrap(), 6);
         assert_eq!(
-            add_numbers_check_red(
-                &"[1,{{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5},\"b\":2},3]".to_string()
-            ),
+            add_numbers_check_red(&parse(
+                "[1,{{\"d\":\"red\",\"e\":[1,2,3,4],\"f\":5},\"b\":2},3]"
+            ))
+            .unwrap(),
             6
         )
     }
+
+    #[test]
+    fn test_malformed_json() {
+        assert_eq!(add_numbers(&parse("1")).unwrap(), 1);
+        assert_eq!(add_numbers_check_red(&parse("-12")).unwrap(), -12);
+        assert!(add_numbers(&parse("[1,-]")).is_err());
+        assert!(add_numbers_check_red(&parse("{\"a\":1")).is_err());
+        assert!(add_numbers_check_red(&parse("[{\"a\":[")).is_err());
+    }
 }
@@ -1,9 +1,8 @@
+use crate::error::{field, parse_field, Error, Result};
+use crate::graph::{Goal, Graph, Tour};
 use crate::solver::Solver;
-use itertools::Itertools;
 use std::collections::HashMap;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Guest {
     name: String,
@@ -21,18 +20,19 @@ impl Clone for Guest {
 
 pub struct Problem;
 
+register_solver!(2015, 13, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Guest>;
     type Output1 = isize;
     type Output2 = isize;
 
-    fn parse_input(&self, file: File) -> Vec<Guest> {
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Guest>> {
         let mut guests: Vec<Guest> = Vec::new();
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().for_each(|l| {
-            let line = l.unwrap();
+        for (i, l) in reader.lines().enumerate() {
+            let line = l?;
             let line_split: Vec<&str> = line.split(' ').collect();
-            let guest = line_split[0];
+            let guest = field(i + 1, &line, &line_split, 0)?;
 
             if !guests.iter().any(|g| g.name == guest) {
                 guests.push(Guest {
@@ -41,9 +41,20 @@ impl Solver for Problem {
                 });
             }
 
-            let gain = if line_split[2] == "gain" { true } else { false };
-            let happiness = line_split[3].parse::<isize>().unwrap();
-            let neighbour = line_split[10].trim_end_matches(".");
+            let gain = match field(i + 1, &line, &line_split, 2)? {
+                "gain" => true,
+                "lose" => false,
+                other => {
+                    return Err(Error::at_token(
+                        i + 1,
+                        &line,
+                        other,
+                        format!("expected 'gain' or 'lose', found '{}'", other),
+                    ))
+                }
+            };
+            let happiness: isize = parse_field(i + 1, &line, &line_split, 3)?;
+            let neighbour = field(i + 1, &line, &line_split, 10)?.trim_end_matches('.');
 
             let neighbours = &mut guests
                 .iter_mut()
@@ -53,51 +64,44 @@ impl Solver for Problem {
 
             neighbours.insert(
                 neighbour.to_string(),
-                if gain { happiness } else { -1 * happiness },
+                if gain { happiness } else { -happiness },
             );
-        });
+        }
 
-        guests
+        Ok(guests)
     }
 
-    fn solve_part_one(&self, input: &Vec<Guest>) -> isize {
+    fn solve_part_one(&self, input: &Vec<Guest>) -> Result<isize> {
         find_best_pairings(input)
     }
 
-    fn solve_part_two(&self, input: &Vec<Guest>) -> isize {
-        add_self_to_list(&mut input.to_vec());
-        find_best_pairings(input)
+    fn solve_part_two(&self, input: &Vec<Guest>) -> Result<isize> {
+        let mut guests = input.to_vec();
+        add_self_to_list(&mut guests);
+        find_best_pairings(&guests)
     }
 }
 
-fn find_best_pairings(guests: &Vec<Guest>) -> isize {
-    let permutations = guests.iter().permutations(guests.len());
-    let mut best_happiness = isize::MIN;
-    for permutation in permutations {
-        let mut happiness = 0;
-        for (index, guest) in permutation.iter().enumerate() {
-            let left_index = if index == 0 {
-                permutation.len() - 1
-            } else {
-                index - 1
-            };
-            let right_index = if index == permutation.len() - 1 {
-                0
-            } else {
-                index + 1
-            };
-
-            happiness = happiness
-                + guest.neighbours[&permutation[left_index].name]
-                + guest.neighbours[&permutation[right_index].name];
-        }
-
-        if happiness > best_happiness {
-            best_happiness = happiness;
+// The seating is a cycle through every guest where sitting next to each other
+// is worth what both neighbours gain
+fn find_best_pairings(guests: &[Guest]) -> Result<isize> {
+    let mut table = Graph::new();
+    for guest in guests {
+        table.add_node(guest.name.as_str());
+        for (neighbour, happiness) in &guest.neighbours {
+            let back = guests
+                .iter()
+                .find(|g| g.name == *neighbour)
+                .and_then(|g| g.neighbours.get(&guest.name));
+            if let Some(back) = back {
+                table.add_edge(guest.name.as_str(), neighbour.as_str(), happiness + back);
+            }
         }
     }
 
-    best_happiness
+    table
+        .held_karp(Tour::Cycle, Goal::Longest)?
+        .ok_or_else(|| Error::solve("the guests cannot all be seated next to each other"))
 }
 
 fn add_self_to_list(input: &mut Vec<Guest>) {
@@ -120,38 +124,24 @@ mod tests {
 
     #[test]
     fn test_find_best_pairings() {
-        let mut guests = vec![
-            Guest {
-                name: "Alice".to_string(),
-                neighbours: HashMap::new(),
-            },
-            Guest {
-                name: "Bob".to_string(),
-                neighbours: HashMap::new(),
-            },
-            Guest {
-                name: "Carol".to_string(),
-                neighbours: HashMap::new(),
-            },
-            Guest {
-                name: "David".to_string(),
-                neighbours: HashMap::new(),
-            },
-        ];
-
-        guests[0].neighbours.insert("Bob".to_string(), 54);
-        guests[0].neighbours.insert("Carol".to_string(), -79);
-        guests[0].neighbours.insert("David".to_string(), -2);
-        guests[1].neighbours.insert("Alice".to_string(), 83);
-        guests[1].neighbours.insert("Carol".to_string(), -7);
-        guests[1].neighbours.insert("David".to_string(), -63);
-        guests[2].neighbours.insert("Alice".to_string(), -62);
-        guests[2].neighbours.insert("Bob".to_string(), 60);
-        guests[2].neighbours.insert("David".to_string(), 55);
-        guests[3].neighbours.insert("Alice".to_string(), 46);
-        guests[3].neighbours.insert("Bob".to_string(), -7);
-        guests[3].neighbours.insert("Carol".to_string(), 41);
-
-        assert_eq!(find_best_pairings(&guests), 330);
+        let guests = Problem
+            .parse_str(
+                "Alice would gain 54 happiness units by sitting next to Bob.\n\
+                 Alice would lose 79 happiness units by sitting next to Carol.\n\
+                 Alice would lose 2 happiness units by sitting next to David.\n\
+                 Bob would gain 83 happiness units by sitting next to Alice.\n\
+                 Bob would lose 7 happiness units by sitting next to Carol.\n\
+                 Bob would lose 63 happiness units by sitting next to David.\n\
+                 Carol would lose 62 happiness units by sitting next to Alice.\n\
+                 Carol would gain 60 happiness units by sitting next to Bob.\n\
+                 Carol would gain 55 happiness units by sitting next to David.\n\
+                 David would gain 46 happiness units by sitting next to Alice.\n\
+                 David would lose 7 happiness units by sitting next to Bob.\n\
+                 David would gain 41 happiness units by sitting next to Carol.\n",
+            )
+            .unwrap();
+
+        assert_eq!(find_best_pairings(&guests).unwrap(), 330);
+        assert_eq!(Problem.solve_part_two(&guests).unwrap(), 286);
     }
 }
@@ -1,54 +1,54 @@
+use crate::error::{Error, Result};
+use crate::parse;
 use crate::solver::Solver;
 use itertools::Itertools;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 1, Problem);
+
 impl Solver for Problem {
     type Input = Vec<usize>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<usize> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
-            .lines()
-            .map(|l| l.unwrap().parse::<usize>().unwrap())
-            .collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<usize>> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        parse::numbers(&text)
     }
 
-    fn solve_part_one(&self, input: &Vec<usize>) -> usize {
+    fn solve_part_one(&self, input: &Vec<usize>) -> Result<usize> {
+        let entries =
+            expense_entries(input, 2).ok_or_else(|| Error::solve("no two entries sum to 2020"))?;
         let mut mult = 1;
-        expense_entries(&input, 2)
-            .iter()
-            .for_each(|e| mult = mult * *e);
-        mult
+        entries.iter().for_each(|e| mult *= *e);
+        Ok(mult)
     }
 
-    fn solve_part_two(&self, input: &Vec<usize>) -> usize {
+    fn solve_part_two(&self, input: &Vec<usize>) -> Result<usize> {
+        let entries = expense_entries(input, 3)
+            .ok_or_else(|| Error::solve("no three entries sum to 2020"))?;
         let mut mult = 1;
-        expense_entries(&input, 3)
-            .iter()
-            .for_each(|e| mult = mult * *e);
-        mult
+        entries.iter().for_each(|e| mult *= *e);
+        Ok(mult)
     }
 }
 
-fn expense_entries(input: &Vec<usize>, number_combinations: usize) -> Vec<&usize> {
+fn expense_entries(input: &[usize], number_combinations: usize) -> Option<Vec<&usize>> {
     let combinations = input.iter().combinations(number_combinations);
     for combination in combinations {
         let mut sum = 0;
         for entry in &combination {
-            sum = sum + *entry;
+            sum += *entry;
         }
         if sum == 2020 {
-            return combination;
+            return Some(combination);
         }
     }
 
-    panic!("No combination found!");
+    None
 }
 
 #[cfg(test)]
@@ -57,9 +57,13 @@ mod tests {
 
     #[test]
     fn test_expense_entries() {
-        let ex = vec![1721, 979, 366, 299, 675, 1456];
+        let ex = Problem
+            .parse_str("1721\n979\n366\n299\n675\n1456\n")
+            .unwrap();
 
-        assert_eq!(expense_entries(&ex, 2), vec![&1721, &299]);
-        assert_eq!(expense_entries(&ex, 3), vec![&979, &366, &675]);
+        assert_eq!(expense_entries(&ex, 2), Some(vec![&1721, &299]));
+        assert_eq!(expense_entries(&ex, 3), Some(vec![&979, &366, &675]));
+        assert_eq!(Problem.solve_part_one(&ex).unwrap(), 514579);
+        assert_eq!(Problem.solve_part_two(&ex).unwrap(), 241861950);
     }
 }
@@ -1,7 +1,6 @@
+use crate::error::{field, parse_token, Error, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct PasswordValidator {
     min: usize,
@@ -13,10 +12,10 @@ pub struct PasswordValidator {
 impl PasswordValidator {
     fn new(min: usize, max: usize, letter: char, password: String) -> PasswordValidator {
         PasswordValidator {
-            min: min,
-            max: max,
-            letter: letter,
-            password: password,
+            min,
+            max,
+            letter,
+            password,
         }
     }
 
@@ -26,46 +25,62 @@ impl PasswordValidator {
     }
 
     fn validate_new_rules(&self) -> bool {
-        (self.password.chars().nth(self.min - 1).unwrap() == self.letter)
-            ^ (self.password.chars().nth(self.max - 1).unwrap() == self.letter)
+        (self.password.chars().nth(self.min - 1) == Some(self.letter))
+            ^ (self.password.chars().nth(self.max - 1) == Some(self.letter))
     }
 }
 
 pub struct Problem;
 
+register_solver!(2020, 2, Problem);
+
 impl Solver for Problem {
     type Input = Vec<PasswordValidator>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<PasswordValidator> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<PasswordValidator>> {
+        reader
             .lines()
-            .map(|l| {
-                let line = l.unwrap();
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
                 let row_split: Vec<&str> = line.split(' ').collect();
-                let range_split: Vec<usize> = row_split[0]
+                let range = field(i + 1, &line, &row_split, 0)?;
+                let range_split = range
                     .split('-')
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect();
-                let letter = row_split[1].chars().nth(0).unwrap();
-                PasswordValidator::new(
+                    .map(|s| parse_token::<usize>(i + 1, &line, s))
+                    .collect::<Result<Vec<usize>>>()?;
+                if range_split.len() != 2 || range_split[0] == 0 || range_split[0] > range_split[1]
+                {
+                    return Err(Error::at_token(
+                        i + 1,
+                        &line,
+                        range,
+                        format!("invalid range '{}'", range),
+                    ));
+                }
+                let letter_field = field(i + 1, &line, &row_split, 1)?;
+                let letter = letter_field.chars().next().ok_or_else(|| {
+                    Error::at_token(i + 1, &line, letter_field, "expected a letter")
+                })?;
+                let password = field(i + 1, &line, &row_split, 2)?;
+                Ok(PasswordValidator::new(
                     range_split[0],
                     range_split[1],
                     letter,
-                    row_split[2].to_string(),
-                )
+                    password.to_string(),
+                ))
             })
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<PasswordValidator>) -> usize {
-        input.iter().filter(|i| i.validate()).count()
+    fn solve_part_one(&self, input: &Vec<PasswordValidator>) -> Result<usize> {
+        Ok(input.iter().filter(|i| i.validate()).count())
     }
 
-    fn solve_part_two(&self, input: &Vec<PasswordValidator>) -> usize {
-        input.iter().filter(|i| i.validate_new_rules()).count()
+    fn solve_part_two(&self, input: &Vec<PasswordValidator>) -> Result<usize> {
+        Ok(input.iter().filter(|i| i.validate_new_rules()).count())
     }
 }
 
@@ -73,25 +88,25 @@ impl Solver for Problem {
 mod tests {
     use crate::y2020::day02::*;
 
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
     #[test]
     fn test_password_validate() {
-        let ex1 = PasswordValidator::new(1, 3, 'a', "abcde".to_string());
-        let ex2 = PasswordValidator::new(1, 3, 'b', "cdefg".to_string());
-        let ex3 = PasswordValidator::new(2, 9, 'c', "ccccccccc".to_string());
+        let validators = Problem.parse_str(EXAMPLE).unwrap();
 
-        assert_eq!(ex1.validate(), true);
-        assert_eq!(ex2.validate(), false);
-        assert_eq!(ex3.validate(), true);
+        assert!(validators[0].validate());
+        assert!(!validators[1].validate());
+        assert!(validators[2].validate());
+        assert_eq!(Problem.solve_part_one(&validators).unwrap(), 2);
     }
 
     #[test]
     fn test_password_validate_new_rules() {
-        let ex1 = PasswordValidator::new(1, 3, 'a', "abcde".to_string());
-        let ex2 = PasswordValidator::new(1, 3, 'b', "cdefg".to_string());
-        let ex3 = PasswordValidator::new(2, 9, 'c', "ccccccccc".to_string());
+        let validators = Problem.parse_str(EXAMPLE).unwrap();
 
-        assert_eq!(ex1.validate_new_rules(), true);
-        assert_eq!(ex2.validate_new_rules(), false);
-        assert_eq!(ex3.validate_new_rules(), false);
+        assert!(validators[0].validate_new_rules());
+        assert!(!validators[1].validate_new_rules());
+        assert!(!validators[2].validate_new_rules());
+        assert_eq!(Problem.solve_part_two(&validators).unwrap(), 1);
     }
 }
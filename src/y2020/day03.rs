@@ -1,56 +1,51 @@
+use crate::error::Result;
+use crate::grid::Grid;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 3, Problem);
+
 impl Solver for Problem {
-    type Input = Vec<String>;
+    type Input = Grid<bool>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    // Trees are `true`
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Grid<bool>> {
+        let mut map = String::new();
+        reader.read_to_string(&mut map)?;
+        Grid::parse(&map, "expected '.' or '#'", |c| match c {
+            '.' => Some(false),
+            '#' => Some(true),
+            _ => None,
+        })
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        traverse_map(input, 3, 1)
+    fn solve_part_one(&self, input: &Grid<bool>) -> Result<usize> {
+        Ok(traverse_map(input, 3, 1))
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
+    fn solve_part_two(&self, input: &Grid<bool>) -> Result<usize> {
         let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
         let mut mult = 1;
 
         for slope in slopes.iter() {
-            mult = mult * traverse_map(input, slope.0, slope.1);
+            mult *= traverse_map(input, slope.0, slope.1);
         }
 
-        mult
+        Ok(mult)
     }
 }
 
-fn traverse_map(input: &Vec<String>, right: usize, down: usize) -> usize {
-    let mut trees = 0;
-    let mut x_index = 0;
-    let mut y_index = 0;
-
-    loop {
-        let line = &input[y_index];
-
-        if line.chars().nth(x_index).unwrap() == '#' {
-            trees = trees + 1;
-        }
-        x_index = (x_index + right) % line.len();
-
-        y_index = y_index + down;
-        if y_index >= input.len() {
-            break;
-        }
-    }
-
-    trees
+// The map repeats to the right, so only going down can leave it
+fn traverse_map(input: &Grid<bool>, right: usize, down: usize) -> usize {
+    (0..input.height())
+        .step_by(down)
+        .enumerate()
+        .filter(|&(step, y)| *input.get_wrapping((step * right) as isize, y as isize))
+        .count()
 }
 
 #[cfg(test)]
@@ -59,24 +54,27 @@ mod tests {
 
     #[test]
     fn test_traverse_map() {
-        let ex = vec![
-            "..##.......".to_string(),
-            "#...#...#..".to_string(),
-            ".#....#..#.".to_string(),
-            "..#.#...#.#".to_string(),
-            ".#...##..#.".to_string(),
-            "..#.##.....".to_string(),
-            ".#.#.#....#".to_string(),
-            ".#........#".to_string(),
-            "#.##...#...".to_string(),
-            "#...##....#".to_string(),
-            ".#..#...#.#".to_string(),
-        ];
+        let ex = Problem
+            .parse_str(
+                "..##.......\n\
+                 #...#...#..\n\
+                 .#....#..#.\n\
+                 ..#.#...#.#\n\
+                 .#...##..#.\n\
+                 ..#.##.....\n\
+                 .#.#.#....#\n\
+                 .#........#\n\
+                 #.##...#...\n\
+                 #...##....#\n\
+                 .#..#...#.#\n",
+            )
+            .unwrap();
 
         assert_eq!(traverse_map(&ex, 1, 1), 2);
         assert_eq!(traverse_map(&ex, 3, 1), 7);
         assert_eq!(traverse_map(&ex, 5, 1), 3);
         assert_eq!(traverse_map(&ex, 7, 1), 4);
         assert_eq!(traverse_map(&ex, 1, 2), 2);
+        assert_eq!(Problem.solve_part_two(&ex).unwrap(), 336);
     }
 }
@@ -1,29 +1,55 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 5, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
+                for (j, c) in line.chars().enumerate() {
+                    let valid = if j < 7 {
+                        c == 'F' || c == 'B'
+                    } else {
+                        c == 'L' || c == 'R'
+                    };
+                    if !valid {
+                        return Err(Error::parse_at(i + 1, j + 1, format!("unexpected '{}'", c)));
+                    }
+                }
+                if line.len() != 10 {
+                    return Err(Error::parse_at(i + 1, 1, "expected 10 characters"));
+                }
+                Ok(line)
+            })
+            .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        input.iter().map(|s| seat_id(s)).max().unwrap()
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        input
+            .iter()
+            .map(|s| seat_id(s))
+            .max()
+            .ok_or_else(|| Error::solve("no boarding passes"))
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
         let mut seat_ids: Vec<usize> = input.iter().map(|s| seat_id(s)).collect();
         seat_ids.sort();
 
-        let first = *seat_ids.first().unwrap();
+        let first = *seat_ids
+            .first()
+            .ok_or_else(|| Error::solve("no boarding passes"))?;
         let last = *seat_ids.last().unwrap();
 
         let mut my_seat = None;
@@ -33,28 +59,28 @@ impl Solver for Problem {
                 break;
             }
         }
-        my_seat.unwrap()
+        my_seat.ok_or_else(|| Error::solve("no free seat found"))
     }
 }
 
-fn seat_id(seat_binary: &String) -> usize {
-    let mut row_lower_bound = 0;
-    let mut row_upper_bound = 127;
-    let mut col_lower_bound = 0;
-    let mut col_upper_bound = 7;
+fn seat_id(seat_binary: &str) -> usize {
+    let mut row_lower_bound: usize = 0;
+    let mut row_upper_bound: usize = 127;
+    let mut col_lower_bound: usize = 0;
+    let mut col_upper_bound: usize = 7;
     for char in seat_binary.chars() {
         match char {
             'F' => {
-                row_upper_bound = row_upper_bound - ((row_upper_bound - row_lower_bound + 1) / 2)
+                row_upper_bound = row_upper_bound - (row_upper_bound - row_lower_bound).div_ceil(2)
             }
             'B' => {
-                row_lower_bound = row_lower_bound + ((row_upper_bound - row_lower_bound + 1) / 2)
+                row_lower_bound = row_lower_bound + (row_upper_bound - row_lower_bound).div_ceil(2)
             }
             'L' => {
-                col_upper_bound = col_upper_bound - ((col_upper_bound - col_lower_bound + 1) / 2)
+                col_upper_bound = col_upper_bound - (col_upper_bound - col_lower_bound).div_ceil(2)
             }
             'R' => {
-                col_lower_bound = col_lower_bound + ((col_upper_bound - col_lower_bound + 1) / 2)
+                col_lower_bound = col_lower_bound + (col_upper_bound - col_lower_bound).div_ceil(2)
             }
             _ => panic!("Unexpected char in row"),
         }
@@ -72,9 +98,14 @@ mod tests {
 
     #[test]
     fn test_seat_id() {
-        assert_eq!(seat_id(&"FBFBBFFRLR".to_string()), 357);
-        assert_eq!(seat_id(&"BFFFBBFRRR".to_string()), 567);
-        assert_eq!(seat_id(&"FFFBBBFRRR".to_string()), 119);
-        assert_eq!(seat_id(&"BBFFBBFRLL".to_string()), 820);
+        let seats = Problem
+            .parse_str("FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL\n")
+            .unwrap();
+
+        assert_eq!(seat_id(&seats[0]), 357);
+        assert_eq!(seat_id(&seats[1]), 567);
+        assert_eq!(seat_id(&seats[2]), 119);
+        assert_eq!(seat_id(&seats[3]), 820);
+        assert_eq!(Problem.solve_part_one(&seats).unwrap(), 820);
     }
 }
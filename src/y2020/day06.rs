@@ -1,57 +1,39 @@
+use crate::error::Result;
+use crate::parse;
 use crate::solver::Solver;
 use std::collections::{HashMap, HashSet};
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 6, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Vec<String>>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<Vec<String>> {
-        let buf_reader = BufReader::new(file);
-        let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap()).collect();
-        get_groups(lines)
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Vec<String>>> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        Ok(get_groups(&text))
     }
 
-    fn solve_part_one(&self, input: &Vec<Vec<String>>) -> usize {
-        input.iter().map(|g| count_answers(g)).sum()
+    fn solve_part_one(&self, input: &Vec<Vec<String>>) -> Result<usize> {
+        Ok(input.iter().map(count_answers).sum())
     }
 
-    fn solve_part_two(&self, input: &Vec<Vec<String>>) -> usize {
-        input.iter().map(|g| count_answers_everyone(g)).sum()
+    fn solve_part_two(&self, input: &Vec<Vec<String>>) -> Result<usize> {
+        Ok(input.iter().map(count_answers_everyone).sum())
     }
 }
 
-fn get_groups(lines: Vec<String>) -> Vec<Vec<String>> {
-    let mut groups: Vec<Vec<String>> = Vec::new();
-    let mut index = 0;
-    loop {
-        if index == lines.len() {
-            break;
-        }
-
-        let mut group = Vec::new();
-        loop {
-            if index == lines.len() {
-                break;
-            }
-
-            let line = &lines[index];
-            index = index + 1;
-            if line == "" {
-                break;
-            }
-
-            group.push(line.to_string());
-        }
-        groups.push(group);
-    }
-
-    groups
+// The answers of every person, grouped by the blank lines between groups
+fn get_groups(text: &str) -> Vec<Vec<String>> {
+    parse::paragraphs(text)
+        .iter()
+        .map(|group| group.iter().map(|(_, person)| person.to_string()).collect())
+        .collect()
 }
 
 fn count_answers(group: &Vec<String>) -> usize {
@@ -82,7 +64,7 @@ fn count_answers_everyone(group: &Vec<String>) -> usize {
     let mut answers_everyone = 0;
     for (_k, v) in answers.drain() {
         if v == group.len() {
-            answers_everyone = answers_everyone + 1;
+            answers_everyone += 1;
         }
     }
 
@@ -93,57 +75,29 @@ fn count_answers_everyone(group: &Vec<String>) -> usize {
 mod tests {
     use crate::y2020::day06::*;
 
+    const EXAMPLE: &str = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb\n";
+
     #[test]
     fn test_count_answers() {
-        let groups = get_groups(vec![
-            "abc".to_string(),
-            "".to_string(),
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "".to_string(),
-            "ab".to_string(),
-            "ac".to_string(),
-            "".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "".to_string(),
-            "b".to_string(),
-        ]);
+        let groups = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(count_answers(&groups[0]), 3);
         assert_eq!(count_answers(&groups[1]), 3);
         assert_eq!(count_answers(&groups[2]), 3);
         assert_eq!(count_answers(&groups[3]), 1);
         assert_eq!(count_answers(&groups[4]), 1);
+        assert_eq!(Problem.solve_part_one(&groups).unwrap(), 11);
     }
 
     #[test]
     fn test_count_answers_everyone() {
-        let groups = get_groups(vec![
-            "abc".to_string(),
-            "".to_string(),
-            "a".to_string(),
-            "b".to_string(),
-            "c".to_string(),
-            "".to_string(),
-            "ab".to_string(),
-            "ac".to_string(),
-            "".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "a".to_string(),
-            "".to_string(),
-            "b".to_string(),
-        ]);
+        let groups = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(count_answers_everyone(&groups[0]), 3);
         assert_eq!(count_answers_everyone(&groups[1]), 0);
         assert_eq!(count_answers_everyone(&groups[2]), 1);
         assert_eq!(count_answers_everyone(&groups[3]), 1);
         assert_eq!(count_answers_everyone(&groups[4]), 1);
+        assert_eq!(Problem.solve_part_two(&groups).unwrap(), 6);
     }
 }
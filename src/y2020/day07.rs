@@ -1,150 +1,147 @@
+use crate::error::{field, parse_field, Error, Result};
+use crate::graph::Graph;
 use crate::solver::Solver;
+use std::io;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashSet, fs::File};
 
-pub struct BagRule {
-    holder: String,
-    content: Option<String>,
-    amount: u8,
-}
+// Every bag colour with an edge to each colour it directly contains, weighted by
+// how many of them it contains
+pub type Bags = Graph<String, usize>;
 
 pub struct Problem;
 
+register_solver!(2020, 7, Problem);
+
 impl Solver for Problem {
-    type Input = Vec<BagRule>;
+    type Input = Bags;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<BagRule> {
-        let buf_reader = BufReader::new(file);
-        let lines: Vec<String> = buf_reader.lines().map(|l| l.unwrap()).collect();
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Bags> {
+        let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
         get_bag_rules(lines)
     }
 
-    fn solve_part_one(&self, input: &Vec<BagRule>) -> usize {
-        let mut holders = HashSet::new();
-        bag_holders("shiny gold".to_string(), &mut holders, &input);
-        holders.len()
+    fn solve_part_one(&self, input: &Bags) -> Result<usize> {
+        Ok(bag_holders("shiny gold", input))
     }
 
-    fn solve_part_two(&self, input: &Vec<BagRule>) -> usize {
-        bag_content("shiny gold".to_string(), &input)
+    fn solve_part_two(&self, input: &Bags) -> Result<usize> {
+        bag_content("shiny gold", input)
     }
 }
 
-fn get_bag_rules(lines: Vec<String>) -> Vec<BagRule> {
-    let mut bag_rules: Vec<BagRule> = Vec::new();
-    for line in lines {
+fn get_bag_rules(lines: Vec<String>) -> Result<Bags> {
+    let mut bags = Graph::new();
+    for (line_index, line) in lines.iter().enumerate() {
+        let line_number = line_index + 1;
         let line_split = line.split(' ').collect::<Vec<&str>>();
-        let holder = format!("{} {}", line_split[0], line_split[1]);
+        let holder = format!(
+            "{} {}",
+            field(line_number, line, &line_split, 0)?,
+            field(line_number, line, &line_split, 1)?
+        );
 
         if line_split.len() < 8 {
-            bag_rules.push(BagRule {
-                amount: 0,
-                content: None,
-                holder: holder.to_string(),
-            });
+            bags.add_node(holder);
             continue;
         }
 
         let number_of_bags = (line_split.len() - 8) / 4 + 1;
         for i in 0..number_of_bags {
             let index = 4 * (i + 1);
-            let amount = line_split[index].parse::<u8>().unwrap();
-            let content = format!("{} {}", line_split[index + 1], line_split[index + 2]);
-
-            bag_rules.push(BagRule {
-                amount: amount,
-                content: Some(content),
-                holder: holder.to_string(),
-            });
+            let amount: u8 = parse_field(line_number, line, &line_split, index)?;
+            let content = format!(
+                "{} {}",
+                field(line_number, line, &line_split, index + 1)?,
+                field(line_number, line, &line_split, index + 2)?
+            );
+
+            bags.add_edge(holder.to_string(), content, amount as usize);
         }
     }
 
-    bag_rules
+    Ok(bags)
 }
 
-fn bag_holders(target: String, holders: &mut HashSet<String>, bag_rules: &Vec<BagRule>) {
-    bag_rules
-        .iter()
-        .filter(|rule| match &rule.content {
-            Some(c) => c == &target,
-            None => false,
-        })
-        .for_each(|rule| {
-            holders.insert(rule.holder.to_string());
-            bag_holders(rule.holder.to_string(), holders, bag_rules);
-        });
-}
-
-fn bag_content(target: String, bag_rules: &Vec<BagRule>) -> usize {
-    let rules = bag_rules.iter().filter(|rule| rule.holder == target);
-
-    let mut bags = 0;
-
-    for rule in rules {
-        bags = bags + rule.amount as usize;
-        match &rule.content {
-            Some(content) => {
-                bags = bags + (rule.amount as usize) * bag_content(content.to_string(), bag_rules);
-            }
-            None => (),
-        }
+// The number of colours that eventually contain a `target` bag
+fn bag_holders(target: &str, bags: &Bags) -> usize {
+    match bags.index(target) {
+        Some(index) => bags.reversed().bfs(index).iter().flatten().count() - 1,
+        None => 0,
     }
+}
 
-    bags
+fn bag_content(target: &str, bags: &Bags) -> Result<usize> {
+    let index = match bags.index(target) {
+        Some(index) => index,
+        None => return Ok(0),
+    };
+
+    bags.fold(index, |_, contents| {
+        contents
+            .iter()
+            .map(|(amount, inner)| *amount * (1 + *inner))
+            .sum()
+    })
+    .map_err(|bag| {
+        Error::solve(format!(
+            "{} bags end up containing themselves",
+            bags.node(bag)
+        ))
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::y2020::day07::*;
 
+    const EXAMPLE: &str = "light red bags contain 1 bright white bag, 2 muted yellow bags.\n\
+                           dark orange bags contain 3 bright white bags, 4 muted yellow bags.\n\
+                           bright white bags contain 1 shiny gold bag.\n\
+                           muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\n\
+                           shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\n\
+                           dark olive bags contain 3 faded blue bags, 4 dotted black bags.\n\
+                           vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\n\
+                           faded blue bags contain no other bags.\n\
+                           dotted black bags contain no other bags.\n";
+
     #[test]
     fn test_bag_holders() {
-        let rules = get_bag_rules(vec![
-            "light red bags contain 1 bright white bag, 2 muted yellow bags.".to_string(),
-            "dark orange bags contain 3 bright white bags, 4 muted yellow bags.".to_string(),
-            "bright white bags contain 1 shiny gold bag.".to_string(),
-            "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.".to_string(),
-            "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.".to_string(),
-            "dark olive bags contain 3 faded blue bags, 4 dotted black bags.".to_string(),
-            "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.".to_string(),
-            "faded blue bags contain no other bags.".to_string(),
-            "dotted black bags contain no other bags.".to_string(),
-        ]);
-
-        let mut holders = HashSet::new();
-        bag_holders("shiny gold".to_string(), &mut holders, &rules);
-
-        assert_eq!(holders.len(), 4);
+        let rules = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(bag_holders("shiny gold", &rules), 4);
+        assert_eq!(Problem.solve_part_one(&rules).unwrap(), 4);
     }
 
     #[test]
     fn test_bag_content() {
-        let ex1 = get_bag_rules(vec![
-            "light red bags contain 1 bright white bag, 2 muted yellow bags.".to_string(),
-            "dark orange bags contain 3 bright white bags, 4 muted yellow bags.".to_string(),
-            "bright white bags contain 1 shiny gold bag.".to_string(),
-            "muted yellow bags contain 2 shiny gold bags, 9 faded blue bags.".to_string(),
-            "shiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.".to_string(),
-            "dark olive bags contain 3 faded blue bags, 4 dotted black bags.".to_string(),
-            "vibrant plum bags contain 5 faded blue bags, 6 dotted black bags.".to_string(),
-            "faded blue bags contain no other bags.".to_string(),
-            "dotted black bags contain no other bags.".to_string(),
-        ]);
-
-        let ex2 = get_bag_rules(vec![
-            "shiny gold bags contain 2 dark red bags.".to_string(),
-            "dark red bags contain 2 dark orange bags.".to_string(),
-            "dark orange bags contain 2 dark yellow bags.".to_string(),
-            "dark yellow bags contain 2 dark green bags.".to_string(),
-            "dark green bags contain 2 dark blue bags.".to_string(),
-            "dark blue bags contain 2 dark violet bags.".to_string(),
-            "dark violet bags contain no other bags.".to_string(),
-        ]);
-
-        assert_eq!(bag_content("shiny gold".to_string(), &ex1), 32);
-        assert_eq!(bag_content("shiny gold".to_string(), &ex2), 126);
+        let ex1 = Problem.parse_str(EXAMPLE).unwrap();
+        let ex2 = Problem
+            .parse_str(
+                "shiny gold bags contain 2 dark red bags.\n\
+                 dark red bags contain 2 dark orange bags.\n\
+                 dark orange bags contain 2 dark yellow bags.\n\
+                 dark yellow bags contain 2 dark green bags.\n\
+                 dark green bags contain 2 dark blue bags.\n\
+                 dark blue bags contain 2 dark violet bags.\n\
+                 dark violet bags contain no other bags.\n",
+            )
+            .unwrap();
+
+        assert_eq!(bag_content("shiny gold", &ex1).unwrap(), 32);
+        assert_eq!(bag_content("shiny gold", &ex2).unwrap(), 126);
+        assert_eq!(Problem.solve_part_two(&ex2).unwrap(), 126);
+
+        let cycle = Problem
+            .parse_str(
+                "shiny gold bags contain 2 dark red bags.\n\
+                 dark red bags contain 1 shiny gold bag.\n",
+            )
+            .unwrap();
+        assert_eq!(
+            bag_content("shiny gold", &cycle).unwrap_err().to_string(),
+            "shiny gold bags end up containing themselves"
+        );
     }
 }
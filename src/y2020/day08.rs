@@ -1,7 +1,7 @@
+use crate::error::{field, parse_field, Error, Result};
 use crate::solver::Solver;
+use std::collections::HashSet;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashSet, fs::File};
 
 #[derive(Clone)]
 pub enum Instruction {
@@ -12,39 +12,47 @@ pub enum Instruction {
 
 pub struct Problem;
 
+register_solver!(2020, 8, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Instruction>;
     type Output1 = isize;
     type Output2 = isize;
 
-    fn parse_input(&self, file: File) -> Vec<Instruction> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Instruction>> {
+        reader
             .lines()
-            .map(|l| get_instruction(&l.unwrap()))
+            .enumerate()
+            .map(|(i, l)| get_instruction(i + 1, &l?))
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<Instruction>) -> isize {
-        find_loop(input).0
+    fn solve_part_one(&self, input: &Vec<Instruction>) -> Result<isize> {
+        Ok(find_loop(input).0)
     }
 
-    fn solve_part_two(&self, input: &Vec<Instruction>) -> isize {
-        fix_and_run_instructions(input)
+    fn solve_part_two(&self, input: &Vec<Instruction>) -> Result<isize> {
+        Ok(fix_and_run_instructions(input))
     }
 }
 
-fn get_instruction(line: &String) -> Instruction {
+fn get_instruction(line_number: usize, line: &str) -> Result<Instruction> {
     let line_split: Vec<&str> = line.split(' ').collect();
-    match line_split[0] {
-        "nop" => Instruction::Nop(line_split[1].parse::<isize>().unwrap()),
-        "acc" => Instruction::Acc(line_split[1].parse::<isize>().unwrap()),
-        "jmp" => Instruction::Jmp(line_split[1].parse::<isize>().unwrap()),
-        _ => panic!("Unexpected instruction"),
+    let argument = parse_field::<isize>(line_number, line, &line_split, 1);
+    match field(line_number, line, &line_split, 0)? {
+        "nop" => Ok(Instruction::Nop(argument?)),
+        "acc" => Ok(Instruction::Acc(argument?)),
+        "jmp" => Ok(Instruction::Jmp(argument?)),
+        other => Err(Error::at_token(
+            line_number,
+            line,
+            other,
+            format!("unknown operation '{}'", other),
+        )),
     }
 }
 
-fn find_loop(instructions: &Vec<Instruction>) -> (isize, bool) {
+fn find_loop(instructions: &[Instruction]) -> (isize, bool) {
     let mut executed_instructions: HashSet<isize> = HashSet::new();
     let mut pointer = 0;
     let mut accumulator = 0;
@@ -60,16 +68,16 @@ fn find_loop(instructions: &Vec<Instruction>) -> (isize, bool) {
 
         let instruction = &instructions[pointer as usize];
         executed_instructions.insert(pointer);
-        pointer = pointer + 1;
+        pointer += 1;
         match instruction {
             Instruction::Nop(_n) => continue,
-            Instruction::Acc(a) => accumulator = accumulator + a,
+            Instruction::Acc(a) => accumulator += a,
             Instruction::Jmp(j) => pointer = pointer + j - 1,
         }
     }
 }
 
-fn fix_and_run_instructions(instructions: &Vec<Instruction>) -> isize {
+fn fix_and_run_instructions(instructions: &[Instruction]) -> isize {
     for (i, instruction) in instructions.iter().enumerate() {
         let mut fixed_instructions = instructions.to_vec();
         match instruction {
@@ -94,43 +102,22 @@ fn fix_and_run_instructions(instructions: &Vec<Instruction>) -> isize {
 mod tests {
     use crate::y2020::day08::*;
 
+    const EXAMPLE: &str =
+        "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6\n";
+
     #[test]
     fn test_find_loop() {
-        let instructions = vec![
-            "nop +0".to_string(),
-            "acc +1".to_string(),
-            "jmp +4".to_string(),
-            "acc +3".to_string(),
-            "jmp -3".to_string(),
-            "acc -99".to_string(),
-            "acc +1".to_string(),
-            "jmp -4".to_string(),
-            "acc +6".to_string(),
-        ]
-        .iter()
-        .map(|l| get_instruction(l))
-        .collect::<Vec<Instruction>>();
+        let instructions = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(find_loop(&instructions).0, 5);
+        assert_eq!(Problem.solve_part_one(&instructions).unwrap(), 5);
     }
 
     #[test]
     fn test_fix_and_run_instructions() {
-        let instructions = vec![
-            "nop +0".to_string(),
-            "acc +1".to_string(),
-            "jmp +4".to_string(),
-            "acc +3".to_string(),
-            "jmp -3".to_string(),
-            "acc -99".to_string(),
-            "acc +1".to_string(),
-            "jmp -4".to_string(),
-            "acc +6".to_string(),
-        ]
-        .iter()
-        .map(|l| get_instruction(l))
-        .collect::<Vec<Instruction>>();
+        let instructions = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(fix_and_run_instructions(&instructions), 8);
+        assert_eq!(Problem.solve_part_two(&instructions).unwrap(), 8);
     }
 }
@@ -1,33 +1,34 @@
+use crate::error::{Error, Result};
+use crate::parse;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 9, Problem);
+
 impl Solver for Problem {
     type Input = Vec<usize>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<usize> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
-            .lines()
-            .map(|l| l.unwrap().parse::<usize>().unwrap())
-            .collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<usize>> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        parse::numbers(&text)
     }
 
-    fn solve_part_one(&self, input: &Vec<usize>) -> usize {
-        find_first_fault_in_xmas(input, 25)
+    fn solve_part_one(&self, input: &Vec<usize>) -> Result<usize> {
+        find_first_fault_in_xmas(input, 25).ok_or_else(|| Error::solve("no invalid number found"))
     }
 
-    fn solve_part_two(&self, input: &Vec<usize>) -> usize {
-        find_weakness(input, find_first_fault_in_xmas(input, 25))
+    fn solve_part_two(&self, input: &Vec<usize>) -> Result<usize> {
+        let first_fault = self.solve_part_one(input)?;
+        Ok(find_weakness(input, first_fault))
     }
 }
 
-fn find_first_fault_in_xmas(input: &Vec<usize>, preamble_length: usize) -> usize {
+fn find_first_fault_in_xmas(input: &[usize], preamble_length: usize) -> Option<usize> {
     let mut first_fault = None;
     let mut index = preamble_length;
     for num in input.iter().skip(preamble_length) {
@@ -54,13 +55,13 @@ fn find_first_fault_in_xmas(input: &Vec<usize>, preamble_length: usize) -> usize
             break;
         }
 
-        index = index + 1;
+        index += 1;
     }
 
-    first_fault.unwrap()
+    first_fault
 }
 
-fn find_weakness(input: &Vec<usize>, first_fault: usize) -> usize {
+fn find_weakness(input: &[usize], first_fault: usize) -> usize {
     let mut contiguous_set = Vec::new();
 
     let mut index = 0;
@@ -73,7 +74,7 @@ fn find_weakness(input: &Vec<usize>, first_fault: usize) -> usize {
             contiguous_set.remove(0);
         } else {
             contiguous_set.push(input[index]);
-            index = index + 1;
+            index += 1;
         }
     }
 
@@ -84,22 +85,19 @@ fn find_weakness(input: &Vec<usize>, first_fault: usize) -> usize {
 mod tests {
     use crate::y2020::day09::*;
 
+    const EXAMPLE: &str =
+        "35\n20\n15\n25\n47\n40\n62\n55\n65\n95\n102\n117\n150\n182\n127\n219\n299\n277\n309\n576\n";
+
     #[test]
     fn test_find_first_fault_in_xmas() {
-        let ex = vec![
-            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
-            576,
-        ];
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
 
-        assert_eq!(find_first_fault_in_xmas(&ex, 5), 127);
+        assert_eq!(find_first_fault_in_xmas(&ex, 5), Some(127));
     }
 
     #[test]
     fn test_find_weakness() {
-        let ex = vec![
-            35, 20, 15, 25, 47, 40, 62, 55, 65, 95, 102, 117, 150, 182, 127, 219, 299, 277, 309,
-            576,
-        ];
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(find_weakness(&ex, 127), 62);
     }
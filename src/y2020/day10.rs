@@ -1,30 +1,35 @@
+use crate::error::{Error, Result};
+use crate::parse;
 use crate::solver::Solver;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 10, Problem);
+
 impl Solver for Problem {
     type Input = Vec<usize>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<usize> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
-            .lines()
-            .map(|l| l.unwrap().parse::<usize>().unwrap())
-            .collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<usize>> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let adapters = parse::numbers(&text)?;
+        if adapters.is_empty() {
+            return Err(Error::parse("expected at least one adapter"));
+        }
+        Ok(adapters)
     }
 
-    fn solve_part_one(&self, input: &Vec<usize>) -> usize {
+    fn solve_part_one(&self, input: &Vec<usize>) -> Result<usize> {
         let differences = find_differences(&mut input.to_vec());
-        differences.0 * differences.1
+        Ok(differences.0 * differences.1)
     }
 
-    fn solve_part_two(&self, input: &Vec<usize>) -> usize {
-        find_valid_combinations(&mut input.to_vec())
+    fn solve_part_two(&self, input: &Vec<usize>) -> Result<usize> {
+        Ok(find_valid_combinations(&mut input.to_vec()))
     }
 }
 
@@ -75,27 +80,32 @@ fn find_valid_combinations(adapters: &mut Vec<usize>) -> usize {
 mod tests {
     use crate::y2020::day10::*;
 
+    const EXAMPLE_1: &str = "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4\n";
+    const EXAMPLE_2: &str = "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n\
+                             39\n11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3\n";
+
     #[test]
     fn test_find_differences() {
-        let mut ex1 = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
-        let mut ex2 = vec![
-            28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35,
-            8, 17, 7, 9, 4, 2, 34, 10, 3,
-        ];
+        let mut ex1 = Problem.parse_str(EXAMPLE_1).unwrap();
+        let mut ex2 = Problem.parse_str(EXAMPLE_2).unwrap();
 
+        assert_eq!(Problem.solve_part_one(&ex2).unwrap(), 220);
         assert_eq!(find_differences(&mut ex1), (7, 5));
         assert_eq!(find_differences(&mut ex2), (22, 10));
     }
 
     #[test]
     fn test_find_valid_combinations() {
-        let mut ex1 = vec![16, 10, 15, 5, 1, 11, 7, 19, 6, 12, 4];
-        let mut ex2 = vec![
-            28, 33, 18, 42, 31, 14, 46, 20, 48, 47, 24, 23, 49, 45, 19, 38, 39, 11, 1, 32, 25, 35,
-            8, 17, 7, 9, 4, 2, 34, 10, 3,
-        ];
+        let mut ex1 = Problem.parse_str(EXAMPLE_1).unwrap();
+        let mut ex2 = Problem.parse_str(EXAMPLE_2).unwrap();
 
+        assert_eq!(Problem.solve_part_two(&ex2).unwrap(), 19208);
         assert_eq!(find_valid_combinations(&mut ex1), 8);
         assert_eq!(find_valid_combinations(&mut ex2), 19208);
     }
+
+    #[test]
+    fn test_parse_empty_input() {
+        assert!(Problem.parse_str("").is_err());
+    }
 }
@@ -1,26 +1,50 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 12, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
+                let action = line
+                    .chars()
+                    .next()
+                    .ok_or_else(|| Error::parse_at(i + 1, 1, "expected an instruction"))?;
+                if !"NSEWLRF".contains(action) {
+                    return Err(Error::parse_at(
+                        i + 1,
+                        1,
+                        format!("unknown action '{}'", action),
+                    ));
+                }
+                let value = line[action.len_utf8()..]
+                    .parse::<isize>()
+                    .map_err(|_| Error::parse_at(i + 1, 2, "expected a number"))?;
+                if (action == 'L' || action == 'R') && value % 90 != 0 {
+                    return Err(Error::parse_at(i + 1, 2, "turns must be a multiple of 90"));
+                }
+                Ok(line)
+            })
+            .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        navigate(input)
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(navigate(input))
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
-        navigate_waypoint(input)
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(navigate_waypoint(input))
     }
 }
 
@@ -29,7 +53,7 @@ fn navigate(instructions: &Vec<String>) -> usize {
     let mut face_direction = 'E';
 
     for instruction in instructions {
-        let action = instruction.chars().nth(0).unwrap();
+        let action = instruction.chars().next().unwrap();
         let value = instruction
             .chars()
             .skip(1)
@@ -46,7 +70,7 @@ fn navigate(instructions: &Vec<String>) -> usize {
         }
     }
 
-    coordinates.0.abs() as usize + coordinates.1.abs() as usize
+    coordinates.0.unsigned_abs() + coordinates.1.unsigned_abs()
 }
 
 fn navigate_waypoint(instructions: &Vec<String>) -> usize {
@@ -54,7 +78,7 @@ fn navigate_waypoint(instructions: &Vec<String>) -> usize {
     let mut waypoint = (10, 1);
 
     for instruction in instructions {
-        let action = instruction.chars().nth(0).unwrap();
+        let action = instruction.chars().next().unwrap();
         let value = instruction
             .chars()
             .skip(1)
@@ -73,7 +97,7 @@ fn navigate_waypoint(instructions: &Vec<String>) -> usize {
         }
     }
 
-    coordinates.0.abs() as usize + coordinates.1.abs() as usize
+    coordinates.0.unsigned_abs() + coordinates.1.unsigned_abs()
 }
 
 fn travel_direction(direction: char, value: isize, coordinates: (isize, isize)) -> (isize, isize) {
@@ -92,7 +116,7 @@ fn turn(face_direction: char, value: isize, action: char) -> char {
     let turn_steps = if action == 'R' {
         value / 90
     } else {
-        -1 * value / 90
+        -value / 90
     };
     let current_direction_index = directions
         .iter()
@@ -104,11 +128,11 @@ fn turn(face_direction: char, value: isize, action: char) -> char {
 
 fn rotate_waypoint(waypoint: (isize, isize), value: isize, action: char) -> (isize, isize) {
     if value == 180 {
-        return (-1 * waypoint.0, -1 * waypoint.1);
+        (-waypoint.0, -waypoint.1)
     } else if (value == 90 && action == 'L') || (value == 270 && action == 'R') {
-        return (-1 * waypoint.1, waypoint.0);
+        (-waypoint.1, waypoint.0)
     } else {
-        return (waypoint.1, -1 * waypoint.0);
+        (waypoint.1, -waypoint.0)
     }
 }
 
@@ -116,28 +140,18 @@ fn rotate_waypoint(waypoint: (isize, isize), value: isize, action: char) -> (isi
 mod tests {
     use crate::y2020::day12::*;
 
+    const EXAMPLE: &str = "F10\nN3\nF7\nR90\nF11\n";
+
     #[test]
     fn test_navigate() {
-        let ex = vec![
-            "F10".to_string(),
-            "N3".to_string(),
-            "F7".to_string(),
-            "R90".to_string(),
-            "F11".to_string(),
-        ];
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(navigate(&ex), 25);
     }
 
     #[test]
     fn test_navigate_waypoint() {
-        let ex = vec![
-            "F10".to_string(),
-            "N3".to_string(),
-            "F7".to_string(),
-            "R90".to_string(),
-            "F11".to_string(),
-        ];
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(navigate_waypoint(&ex), 286);
     }
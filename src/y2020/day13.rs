@@ -1,8 +1,10 @@
+use crate::cancel;
+use crate::error::{parse_token, Error, Result};
+use crate::math;
 use crate::solver::Solver;
 use std::collections::HashMap;
-use std::fs::File;
+use std::convert::TryFrom;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Timetable {
     earliest_departure: usize,
@@ -12,18 +14,24 @@ pub struct Timetable {
 
 pub struct Problem;
 
+register_solver!(2020, 13, Problem);
+
 impl Solver for Problem {
     type Input = Timetable;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Timetable {
-        let buf_reader = BufReader::new(file);
-        let mut lines = buf_reader.lines();
-        let earliest_departure = lines.next().unwrap().unwrap().parse::<usize>().unwrap();
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Timetable> {
+        let mut lines = reader.lines();
+        let earliest_line = lines
+            .next()
+            .ok_or_else(|| Error::parse("missing earliest departure"))??;
+        let earliest_departure = parse_token(1, &earliest_line, &earliest_line)?;
         let mut departures = Vec::new();
         let mut departures_with_index = HashMap::new();
-        let departure_line = lines.next().unwrap().unwrap();
+        let departure_line = lines
+            .next()
+            .ok_or_else(|| Error::parse("missing bus departures"))??;
         let split = departure_line.split(',').collect::<Vec<&str>>();
 
         for (i, d) in split.iter().enumerate() {
@@ -31,66 +39,69 @@ impl Solver for Problem {
                 continue;
             }
 
-            let departure = d.to_string().parse::<usize>().unwrap();
+            let departure: usize = parse_token(2, &departure_line, d)?;
+            if departure == 0 {
+                return Err(Error::at_token(
+                    2,
+                    &departure_line,
+                    d,
+                    "bus id must be positive",
+                ));
+            }
             departures.push(departure);
             departures_with_index.insert(departure, i);
         }
 
-        Timetable {
-            earliest_departure: earliest_departure,
-            departures: departures,
-            departures_with_index: departures_with_index,
+        if departures.is_empty() {
+            return Err(Error::parse_at(2, 1, "expected at least one bus id"));
         }
+
+        Ok(Timetable {
+            earliest_departure,
+            departures,
+            departures_with_index,
+        })
     }
 
-    fn solve_part_one(&self, input: &Timetable) -> usize {
+    fn solve_part_one(&self, input: &Timetable) -> Result<usize> {
         find_departure(input)
     }
 
-    fn solve_part_two(&self, input: &Timetable) -> usize {
-        win_contest(input, 100000000000000)
+    fn solve_part_two(&self, input: &Timetable) -> Result<usize> {
+        win_contest(input)
     }
 }
 
-fn find_departure(timetable: &Timetable) -> usize {
+fn find_departure(timetable: &Timetable) -> Result<usize> {
     let mut iterations = 0;
     loop {
+        cancel::check()?;
         let bus = timetable
             .departures
             .iter()
             .find(|d| iterations * **d >= timetable.earliest_departure);
 
-        match bus {
-            Some(b) => {
-                let wait = (iterations * *b) - timetable.earliest_departure;
-                return *b * wait;
-            }
-            None => (),
+        if let Some(b) = bus {
+            let wait = (iterations * *b) - timetable.earliest_departure;
+            return Ok(*b * wait);
         };
 
-        iterations = iterations + 1;
+        iterations += 1;
     }
 }
 
-fn win_contest(timetable: &Timetable, start_timestamp: usize) -> usize {
-    let mut t = start_timestamp;
-    let mut add = 1;
-    let mut iterated = Vec::new();
-    for departure in &timetable.departures {
-        iterated.push(*departure);
-        loop {
-            if iterated
-                .iter()
-                .all(|i| (t + timetable.departures_with_index[i]) % i == 0)
-            {
-                break;
-            }
-            t = t + add;
-        }
-        add = iterated.iter().product();
-    }
-
-    t
+// The earliest `t` where every bus departs at `t` plus its index, so
+// `t ≡ -index (mod id)` for each of them
+fn win_contest(timetable: &Timetable) -> Result<usize> {
+    let congruences: Vec<(i128, i128)> = timetable
+        .departures
+        .iter()
+        .map(|d| (-(timetable.departures_with_index[d] as i128), *d as i128))
+        .collect();
+
+    let (t, _) = math::crt(&congruences)
+        .ok_or_else(|| Error::solve("the buses never depart in the right order"))?;
+    usize::try_from(t).map_err(|_| Error::solve(format!("timestamp {} is too large", t)))
 }
 
 #[cfg(test)]
@@ -99,88 +110,21 @@ mod tests {
 
     #[test]
     fn test_find_departure() {
-        let timetable = Timetable {
-            earliest_departure: 939,
-            departures: vec![7, 13, 59, 31, 19],
-            departures_with_index: HashMap::new(),
-        };
+        let timetable = Problem.parse_str("939\n7,13,x,x,59,x,31,19\n").unwrap();
 
-        assert_eq!(find_departure(&timetable), 295);
+        assert_eq!(find_departure(&timetable).unwrap(), 295);
+        assert_eq!(Problem.solve_part_one(&timetable).unwrap(), 295);
     }
 
     #[test]
     fn test_win_contest() {
-        let mut ex1_index = HashMap::new();
-        ex1_index.insert(7, 0);
-        ex1_index.insert(13, 1);
-        ex1_index.insert(59, 4);
-        ex1_index.insert(31, 6);
-        ex1_index.insert(19, 7);
-        let timetable_ex1 = Timetable {
-            earliest_departure: 0,
-            departures: vec![7, 13, 59, 31, 19],
-            departures_with_index: ex1_index,
-        };
-
-        let mut ex2_index = HashMap::new();
-        ex2_index.insert(17, 0);
-        ex2_index.insert(13, 2);
-        ex2_index.insert(19, 3);
-        let timetable_ex2 = Timetable {
-            earliest_departure: 0,
-            departures: vec![17, 13, 19],
-            departures_with_index: ex2_index,
-        };
-
-        let mut ex3_index = HashMap::new();
-        ex3_index.insert(67, 0);
-        ex3_index.insert(7, 1);
-        ex3_index.insert(59, 2);
-        ex3_index.insert(61, 3);
-        let timetable_ex3 = Timetable {
-            earliest_departure: 0,
-            departures: vec![67, 7, 59, 61],
-            departures_with_index: ex3_index,
-        };
-
-        let mut ex4_index = HashMap::new();
-        ex4_index.insert(67, 0);
-        ex4_index.insert(7, 2);
-        ex4_index.insert(59, 3);
-        ex4_index.insert(61, 4);
-        let timetable_ex4 = Timetable {
-            earliest_departure: 0,
-            departures: vec![67, 7, 59, 61],
-            departures_with_index: ex4_index,
-        };
-
-        let mut ex5_index = HashMap::new();
-        ex5_index.insert(67, 0);
-        ex5_index.insert(7, 1);
-        ex5_index.insert(59, 3);
-        ex5_index.insert(61, 4);
-        let timetable_ex5 = Timetable {
-            earliest_departure: 0,
-            departures: vec![67, 7, 59, 61],
-            departures_with_index: ex5_index,
-        };
-
-        let mut ex6_index = HashMap::new();
-        ex6_index.insert(1789, 0);
-        ex6_index.insert(37, 1);
-        ex6_index.insert(47, 2);
-        ex6_index.insert(1889, 3);
-        let timetable_ex6 = Timetable {
-            earliest_departure: 0,
-            departures: vec![1789, 37, 47, 1889],
-            departures_with_index: ex6_index,
-        };
-
-        assert_eq!(win_contest(&timetable_ex1, 0), 1068781);
-        assert_eq!(win_contest(&timetable_ex2, 0), 3417);
-        assert_eq!(win_contest(&timetable_ex3, 0), 754018);
-        assert_eq!(win_contest(&timetable_ex4, 0), 779210);
-        assert_eq!(win_contest(&timetable_ex5, 0), 1261476);
-        assert_eq!(win_contest(&timetable_ex6, 0), 1202161486);
+        let parse = |departures: &str| Problem.parse_str(&format!("0\n{}\n", departures)).unwrap();
+
+        assert_eq!(win_contest(&parse("7,13,x,x,59,x,31,19")).unwrap(), 1068781);
+        assert_eq!(win_contest(&parse("17,x,13,19")).unwrap(), 3417);
+        assert_eq!(win_contest(&parse("67,7,59,61")).unwrap(), 754018);
+        assert_eq!(win_contest(&parse("67,x,7,59,61")).unwrap(), 779210);
+        assert_eq!(win_contest(&parse("67,7,x,59,61")).unwrap(), 1261476);
+        assert_eq!(win_contest(&parse("1789,37,47,1889")).unwrap(), 1202161486);
     }
 }
@@ -1,9 +1,10 @@
+use crate::error::{Error, Result};
+use crate::parse;
 use crate::solver::Solver;
 use itertools::Itertools;
 use regex::Regex;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct U36 {
     value: [u8; 36],
@@ -13,14 +14,14 @@ impl U36 {
     fn new(dec: u64) -> U36 {
         let mut bin = [0; 36];
         let mut curr_dec = dec;
-        for i in 0..36 {
-            let exp = (2 as u64).pow(35 - i as u32);
+        for (i, bit) in bin.iter_mut().enumerate() {
+            let exp = 2_u64.pow(35 - i as u32);
             if curr_dec < exp {
                 continue;
             }
 
-            bin[i] = 1;
-            curr_dec = curr_dec - exp;
+            *bit = 1;
+            curr_dec -= exp;
         }
 
         U36 { value: bin }
@@ -29,14 +30,14 @@ impl U36 {
     fn to_dec(&self) -> u64 {
         let mut val = 0;
         for i in 0..36 {
-            val = val + self.value[i] as u64 * (2 as u64).pow(35 - i as u32);
+            val += self.value[i] as u64 * 2_u64.pow(35 - i as u32);
         }
 
         val
     }
 
     fn mask(&self, mask: String) -> U36 {
-        let mut masked_bin = self.value.clone();
+        let mut masked_bin = self.value;
 
         for (i, c) in mask.chars().enumerate() {
             if c == '1' {
@@ -54,8 +55,8 @@ impl U36 {
         let mut masked_bins = Vec::new();
         let mut possible_values = Vec::new();
         for _i in 0..number_of_floats {
-            possible_values.push(0 as u8);
-            possible_values.push(1 as u8);
+            possible_values.push(0_u8);
+            possible_values.push(1_u8);
         }
         let combinations = possible_values
             .iter()
@@ -63,14 +64,14 @@ impl U36 {
             .unique();
 
         for combination in combinations {
-            let mut masked_bin = self.value.clone();
+            let mut masked_bin = self.value;
             let mut float_count: usize = 0;
             for (i, c) in mask.chars().enumerate() {
                 if c == '1' {
                     masked_bin[i] = 1;
                 } else if c == 'X' {
-                    masked_bin[i] = *combination[float_count] as u8;
-                    float_count = float_count + 1;
+                    masked_bin[i] = *combination[float_count];
+                    float_count += 1;
                 }
             }
             masked_bins.push(U36 { value: masked_bin }.to_dec());
@@ -87,63 +88,54 @@ pub struct Program {
 
 pub struct Problem;
 
+register_solver!(2020, 14, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Program>;
     type Output1 = u64;
     type Output2 = u64;
 
-    fn parse_input(&self, file: File) -> Vec<Program> {
-        let buf_reader = BufReader::new(file);
-        let mut programs = Vec::new();
-        let mut lines = buf_reader.lines();
-        let mut line = lines.next().unwrap().unwrap();
-        let mut read_all = false;
-        loop {
-            let mask = line.split('=').collect::<Vec<&str>>()[1];
-            let mut program = Program {
-                mask: mask.trim().to_string(),
-                instructions: Vec::new(),
-            };
-
-            loop {
-                let inst = lines.next();
-                match inst {
-                    Some(l) => {
-                        let line_string = l.unwrap();
-                        if line_string.starts_with("mask") {
-                            line = line_string;
-                            break;
-                        }
-
-                        let re = Regex::new(r"^mem\[([0-9]+)\] = ([0-9]+)$").unwrap();
-                        let captures = re.captures(&line_string).unwrap();
-                        let mem_dec = captures[1].parse::<u64>().unwrap();
-                        let val_dec = captures[2].parse::<u64>().unwrap();
-                        program
-                            .instructions
-                            .push((U36::new(mem_dec), U36::new(val_dec)));
-                    }
-                    None => {
-                        read_all = true;
-                        break;
-                    }
-                }
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Program>> {
+        let re = Regex::new(r"^mem\[([0-9]+)\] = ([0-9]+)$").unwrap();
+        let mut programs: Vec<Program> = Vec::new();
+        for (i, l) in reader.lines().enumerate() {
+            let line = l?;
+            if line.starts_with("mask") {
+                let mask = line
+                    .split('=')
+                    .nth(1)
+                    .map(|m| m.trim())
+                    .filter(|m| {
+                        m.len() == 36 && m.chars().all(|c| c == '0' || c == '1' || c == 'X')
+                    })
+                    .ok_or_else(|| Error::parse_at(i + 1, 1, "expected a 36 character mask"))?;
+                programs.push(Program {
+                    mask: mask.to_string(),
+                    instructions: Vec::new(),
+                });
+                continue;
             }
-            programs.push(program);
 
-            if read_all {
-                break;
-            }
+            let captured = parse::captures(&re, i + 1, &line, "expected mem[<address>] = <value>")?;
+            let program = programs.last_mut().ok_or_else(|| {
+                Error::parse_at(i + 1, 1, "expected a mask before the first write")
+            })?;
+            let mem_dec = captured.get::<u64>(1)?;
+            let val_dec = captured.get::<u64>(2)?;
+            program
+                .instructions
+                .push((U36::new(mem_dec), U36::new(val_dec)));
         }
-        programs
+
+        Ok(programs)
     }
 
-    fn solve_part_one(&self, input: &Vec<Program>) -> u64 {
-        execute_program(input)
+    fn solve_part_one(&self, input: &Vec<Program>) -> Result<u64> {
+        Ok(execute_program(input))
     }
 
-    fn solve_part_two(&self, input: &Vec<Program>) -> u64 {
-        execute_program_ver2(input)
+    fn solve_part_two(&self, input: &Vec<Program>) -> Result<u64> {
+        Ok(execute_program_ver2(input))
     }
 }
 
@@ -182,30 +174,28 @@ mod tests {
 
     #[test]
     fn test_execute_program() {
-        let ex = vec![Program {
-            mask: "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X".to_string(),
-            instructions: vec![
-                (U36::new(8), U36::new(11)),
-                (U36::new(7), U36::new(101)),
-                (U36::new(8), U36::new(0)),
-            ],
-        }];
+        let ex = Problem
+            .parse_str(
+                "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\n\
+                 mem[8] = 11\n\
+                 mem[7] = 101\n\
+                 mem[8] = 0\n",
+            )
+            .unwrap();
 
         assert_eq!(execute_program(&ex), 165);
     }
 
     #[test]
     fn test_execute_program_ver2() {
-        let ex = vec![
-            Program {
-                mask: "000000000000000000000000000000X1001X".to_string(),
-                instructions: vec![(U36::new(42), U36::new(100))],
-            },
-            Program {
-                mask: "00000000000000000000000000000000X0XX".to_string(),
-                instructions: vec![(U36::new(26), U36::new(1))],
-            },
-        ];
+        let ex = Problem
+            .parse_str(
+                "mask = 000000000000000000000000000000X1001X\n\
+                 mem[42] = 100\n\
+                 mask = 00000000000000000000000000000000X0XX\n\
+                 mem[26] = 1\n",
+            )
+            .unwrap();
 
         assert_eq!(execute_program_ver2(&ex), 208);
     }
@@ -1,29 +1,32 @@
+use crate::error::{Error, Result};
+use crate::parse;
 use crate::solver::Solver;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 15, Problem);
+
 impl Solver for Problem {
     type Input = Vec<usize>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<usize> {
-        let buf_reader = BufReader::new(file);
-        let line = buf_reader.lines().nth(0).unwrap().unwrap();
-        line.split(',')
-            .map(|n| n.parse::<usize>().unwrap())
-            .collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<usize>> {
+        let line = reader
+            .lines()
+            .next()
+            .ok_or_else(|| Error::parse("input is empty"))??;
+        parse::number_list(1, &line, ',')
     }
 
-    fn solve_part_one(&self, input: &Vec<usize>) -> usize {
-        play_game(input, 2020)
+    fn solve_part_one(&self, input: &Vec<usize>) -> Result<usize> {
+        Ok(play_game(input, 2020))
     }
 
-    fn solve_part_two(&self, input: &Vec<usize>) -> usize {
-        play_game(input, 30000000)
+    fn solve_part_two(&self, input: &Vec<usize>) -> Result<usize> {
+        Ok(play_game(input, 30000000))
     }
 }
 
@@ -34,13 +37,13 @@ fn play_game(start_numbers: &Vec<usize>, end_turn: usize) -> usize {
 
     for start_number in start_numbers {
         next_number = say_number(&mut memory_map, start_number, turn);
-        turn = turn + 1;
+        turn += 1;
     }
 
     loop {
         next_number = say_number(&mut memory_map, &next_number, turn);
 
-        turn = turn + 1;
+        turn += 1;
         if turn == end_turn {
             return next_number;
         }
@@ -48,12 +51,11 @@ fn play_game(start_numbers: &Vec<usize>, end_turn: usize) -> usize {
 }
 
 fn say_number(memory_map: &mut HashMap<usize, usize>, last_number: &usize, turn: usize) -> usize {
-    let next_number: usize;
-    if memory_map.contains_key(&last_number) {
-        next_number = turn - memory_map[&last_number];
+    let next_number = if memory_map.contains_key(last_number) {
+        turn - memory_map[last_number]
     } else {
-        next_number = 0;
-    }
+        0
+    };
 
     memory_map.insert(*last_number, turn);
 
@@ -66,13 +68,13 @@ mod tests {
 
     #[test]
     fn test_play_game() {
-        let start_number_ex1 = vec![0, 3, 6];
-        let start_number_ex2 = vec![1, 3, 2];
-        let start_number_ex3 = vec![2, 1, 3];
-        let start_number_ex4 = vec![1, 2, 3];
-        let start_number_ex5 = vec![2, 3, 1];
-        let start_number_ex6 = vec![3, 2, 1];
-        let start_number_ex7 = vec![3, 1, 2];
+        let start_number_ex1 = Problem.parse_str("0,3,6\n").unwrap();
+        let start_number_ex2 = Problem.parse_str("1,3,2\n").unwrap();
+        let start_number_ex3 = Problem.parse_str("2,1,3\n").unwrap();
+        let start_number_ex4 = Problem.parse_str("1,2,3\n").unwrap();
+        let start_number_ex5 = Problem.parse_str("2,3,1\n").unwrap();
+        let start_number_ex6 = Problem.parse_str("3,2,1\n").unwrap();
+        let start_number_ex7 = Problem.parse_str("3,1,2\n").unwrap();
 
         assert_eq!(play_game(&start_number_ex1, 2020), 436);
         assert_eq!(play_game(&start_number_ex2, 2020), 1);
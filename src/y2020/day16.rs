@@ -1,10 +1,9 @@
-use regex::Regex;
-
+use crate::error::{field, parse_field, Error, Result};
+use crate::parse;
 use crate::solver::Solver;
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Field {
     name: String,
@@ -19,97 +18,88 @@ pub struct Notes {
 
 pub struct Problem;
 
+register_solver!(2020, 16, Problem);
+
 impl Solver for Problem {
     type Input = Notes;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Notes {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Notes> {
         let mut lines = String::new();
-        buf_reader.read_to_string(&mut lines).unwrap();
+        reader.read_to_string(&mut lines)?;
         parse_lines(lines)
     }
 
-    fn solve_part_one(&self, input: &Notes) -> usize {
-        find_error_rate(input)
+    fn solve_part_one(&self, input: &Notes) -> Result<usize> {
+        Ok(find_error_rate(input))
     }
 
-    fn solve_part_two(&self, input: &Notes) -> usize {
+    fn solve_part_two(&self, input: &Notes) -> Result<usize> {
         let mut value = 1;
         let ticket = map_your_ticket(input);
         for (k, v) in ticket {
             if k.starts_with("departure") {
-                value = value * v;
+                value *= v;
             }
         }
-        value
+        Ok(value)
     }
 }
 
-fn parse_lines(lines: String) -> Notes {
-    let re_fields = Regex::new(r"(?P<fields>([a-z\s]+: [0-9]+-[0-9]+ or [0-9]+-[0-9]+)+)").unwrap();
-    let fields = re_fields
-        .captures(&lines)
-        .unwrap()
-        .name("fields")
-        .unwrap()
-        .as_str()
-        .split("\r\n")
-        .filter(|l| *l != "")
-        .map(|c| {
-            let split = c.split(':').collect::<Vec<&str>>();
-            let name = split[0].to_string();
-            let ranges = split[1]
-                .split(" ")
-                .filter(|r| *r != "or" && *r != "")
-                .map(|r| {
-                    let range_split = r.split('-').collect::<Vec<&str>>();
-                    return (
-                        range_split[0].split('\n').collect::<Vec<&str>>()[0]
-                            .parse::<usize>()
-                            .unwrap(),
-                        range_split[1].split('\n').collect::<Vec<&str>>()[0]
-                            .parse::<usize>()
-                            .unwrap(),
-                    );
-                })
-                .collect();
-            Field {
-                name: name,
-                ranges: ranges,
+// The rules, your ticket and the nearby tickets are separated by blank lines,
+// with a header line before each list of tickets
+fn parse_lines(lines: String) -> Result<Notes> {
+    let mut fields = Vec::new();
+    let mut your_ticket = Vec::new();
+    let mut nearby_tickets = Vec::new();
+
+    for paragraph in parse::paragraphs(&lines) {
+        match paragraph[0].1 {
+            "your ticket:" => {
+                for &(line_number, line) in &paragraph[1..] {
+                    your_ticket = parse::number_list(line_number, line, ',')?;
+                }
             }
-        })
-        .collect::<Vec<Field>>();
-
-    let re_your_ticket = Regex::new(r"your ticket:\r\n(?P<your_ticket>([0-9]+,?)+)").unwrap();
-    let your_ticket = re_your_ticket
-        .captures(&lines)
-        .unwrap()
-        .name("your_ticket")
-        .unwrap()
-        .as_str()
-        .split(",")
-        .map(|t| t.parse::<usize>().unwrap())
-        .collect();
-
-    let re_nearby_tickets =
-        Regex::new(r"nearby tickets:\r\n(?P<nearby_tickets>([0-9]+,?(\r\n)?)+)").unwrap();
-    let nearby_tickets = re_nearby_tickets
-        .captures(&lines)
-        .unwrap()
-        .name("nearby_tickets")
-        .unwrap()
-        .as_str()
-        .split("\r\n")
-        .map(|l| l.split(',').map(|t| t.parse::<usize>().unwrap()).collect())
-        .collect();
-
-    Notes {
-        fields: fields,
-        your_ticket: your_ticket,
-        nearby_tickets: nearby_tickets,
+            "nearby tickets:" => {
+                for &(line_number, line) in &paragraph[1..] {
+                    nearby_tickets.push(parse::number_list(line_number, line, ',')?);
+                }
+            }
+            _ => {
+                for &(line_number, line) in &paragraph {
+                    fields.push(parse_field_rule(line_number, line)?);
+                }
+            }
+        }
+    }
+
+    if your_ticket.is_empty() {
+        return Err(Error::parse("missing 'your ticket:' section"));
     }
+
+    Ok(Notes {
+        fields,
+        your_ticket,
+        nearby_tickets,
+    })
+}
+
+fn parse_field_rule(line_number: usize, line: &str) -> Result<Field> {
+    let split = line.split(": ").collect::<Vec<&str>>();
+    let name = field(line_number, line, &split, 0)?.to_string();
+    let ranges = field(line_number, line, &split, 1)?
+        .split(" or ")
+        .map(|r| {
+            let range_split = r.split('-').collect::<Vec<&str>>();
+            Ok((
+                parse_field(line_number, line, &range_split, 0)?,
+                parse_field(line_number, line, &range_split, 1)?,
+            ))
+        })
+        .collect::<Result<Vec<(usize, usize)>>>()?;
+
+    Ok(Field { name, ranges })
 }
 
 fn find_error_rate(notes: &Notes) -> usize {
@@ -122,7 +112,7 @@ fn find_error_rate(notes: &Notes) -> usize {
                 .iter()
                 .any(|f| f.ranges.iter().any(|r| num >= &r.0 && num <= &r.1))
             {
-                error_rate = error_rate + num;
+                error_rate += num;
             }
         }
     }
@@ -165,12 +155,7 @@ fn map_your_ticket(notes: &Notes) -> HashMap<String, usize> {
         .iter()
         .sorted_by(|a, b| Ord::cmp(&a.1.len(), &b.1.len()))
     {
-        let found_field_index = field
-            .1
-            .iter()
-            .filter(|f| !found_fields.contains(f))
-            .nth(0)
-            .unwrap();
+        let found_field_index = field.1.iter().find(|f| !found_fields.contains(f)).unwrap();
 
         found_fields.push(found_field_index);
 
@@ -186,39 +171,44 @@ mod tests {
 
     #[test]
     fn test_find_error_rate() {
-        let ex = parse_lines(
-            "class: 1-3 or 5-7\r\n".to_string()
-                + "row: 6-11 or 33-44\r\n"
-                + "seat: 13-40 or 45-50\r\n"
-                + "\r\n"
-                + "your ticket:\r\n"
-                + "7,1,14\r\n"
-                + "\r\n"
-                + "nearby tickets:\r\n"
-                + "7,3,47\r\n"
-                + "40,4,50\r\n"
-                + "55,2,20\r\n"
-                + "38,6,12",
-        );
+        let ex = Problem
+            .parse_str(
+                &("class: 1-3 or 5-7\r\n".to_string()
+                    + "row: 6-11 or 33-44\r\n"
+                    + "seat: 13-40 or 45-50\r\n"
+                    + "\r\n"
+                    + "your ticket:\r\n"
+                    + "7,1,14\r\n"
+                    + "\r\n"
+                    + "nearby tickets:\r\n"
+                    + "7,3,47\r\n"
+                    + "40,4,50\r\n"
+                    + "55,2,20\r\n"
+                    + "38,6,12"),
+            )
+            .unwrap();
 
         assert_eq!(find_error_rate(&ex), 71);
+        assert_eq!(Problem.solve_part_one(&ex).unwrap(), 71);
     }
 
     #[test]
     fn test_map_your_ticket() {
-        let ex = parse_lines(
-            "class: 0-1 or 4-19\r\n".to_string()
-                + "row: 0-5 or 8-19\r\n"
-                + "seat: 0-13 or 16-19\r\n"
-                + "\r\n"
-                + "your ticket:\r\n"
-                + "11,12,13\r\n"
-                + "\r\n"
-                + "nearby tickets:\r\n"
-                + "3,9,18\r\n"
-                + "15,1,5\r\n"
-                + "5,14,9",
-        );
+        let ex = Problem
+            .parse_str(
+                &("class: 0-1 or 4-19\r\n".to_string()
+                    + "row: 0-5 or 8-19\r\n"
+                    + "seat: 0-13 or 16-19\r\n"
+                    + "\r\n"
+                    + "your ticket:\r\n"
+                    + "11,12,13\r\n"
+                    + "\r\n"
+                    + "nearby tickets:\r\n"
+                    + "3,9,18\r\n"
+                    + "15,1,5\r\n"
+                    + "5,14,9"),
+            )
+            .unwrap();
 
         let your_ticket = map_your_ticket(&ex);
 
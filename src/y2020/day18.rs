@@ -1,29 +1,40 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{fs::File, str::Chars};
+use std::str::Chars;
 
 pub struct Problem;
 
+register_solver!(2020, 18, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
+                match line.find(|c: char| !c.is_ascii_digit() && !" +*()".contains(c)) {
+                    Some(index) => Err(Error::parse_at(i + 1, index + 1, "unexpected character")),
+                    None => Ok(line),
+                }
+            })
+            .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        input.iter().map(|l| calc_string(&mut l.chars())).sum()
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input.iter().map(|l| calc_string(&mut l.chars())).sum())
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
-        input
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(input
             .iter()
             .map(|l| calc_string_with_precedence(&l.chars().collect::<Vec<char>>(), &mut 0, false))
-            .sum()
+            .sum())
     }
 }
 
@@ -73,11 +84,11 @@ fn calc_string_with_precedence(input: &Vec<char>, index: &mut usize, find_additi
             break;
         }
         let c = input[*index];
-        *index = *index + 1;
+        *index += 1;
 
         if c == ')' {
             if find_addition {
-                *index = *index - 1;
+                *index -= 1;
             }
             break;
         } else if c == '(' {
@@ -91,15 +102,15 @@ fn calc_string_with_precedence(input: &Vec<char>, index: &mut usize, find_additi
             current_operator = '+';
         } else if c == '*' {
             if find_addition {
-                *index = *index - 1;
+                *index -= 1;
                 break;
             }
-            val = val * calc_string_with_precedence(input, index, true);
+            val *= calc_string_with_precedence(input, index, true);
         } else if c == ' ' {
             continue;
         } else {
             let parsed_value = c.to_string().parse::<usize>().unwrap();
-            val = val + parsed_value;
+            val += parsed_value;
         }
     }
     val
@@ -109,55 +120,40 @@ fn calc_string_with_precedence(input: &Vec<char>, index: &mut usize, find_additi
 mod tests {
     use crate::y2020::day18::*;
 
+    const EXAMPLE: &str = "1 + 2 * 3 + 4 * 5 + 6\n\
+                           1 + (2 * 3) + (4 * (5 + 6))\n\
+                           2 * 3 + (4 * 5)\n\
+                           5 + (8 * 3 + 9 + 3 * 4 * 3)\n\
+                           5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))\n\
+                           ((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2\n";
+
     #[test]
     fn test_calc_string() {
-        let ex1 = "1 + 2 * 3 + 4 * 5 + 6".to_string();
-        let ex2 = "1 + (2 * 3) + (4 * (5 + 6))".to_string();
-        let ex3 = "2 * 3 + (4 * 5)".to_string();
-        let ex4 = "5 + (8 * 3 + 9 + 3 * 4 * 3)".to_string();
-        let ex5 = "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))".to_string();
-        let ex6 = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2".to_string();
-
-        assert_eq!(calc_string(&mut ex1.chars()), 71);
-        assert_eq!(calc_string(&mut ex2.chars()), 51);
-        assert_eq!(calc_string(&mut ex3.chars()), 26);
-        assert_eq!(calc_string(&mut ex4.chars()), 437);
-        assert_eq!(calc_string(&mut ex5.chars()), 12240);
-        assert_eq!(calc_string(&mut ex6.chars()), 13632);
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
+
+        assert_eq!(calc_string(&mut ex[0].chars()), 71);
+        assert_eq!(calc_string(&mut ex[1].chars()), 51);
+        assert_eq!(calc_string(&mut ex[2].chars()), 26);
+        assert_eq!(calc_string(&mut ex[3].chars()), 437);
+        assert_eq!(calc_string(&mut ex[4].chars()), 12240);
+        assert_eq!(calc_string(&mut ex[5].chars()), 13632);
+        assert_eq!(Problem.solve_part_one(&ex).unwrap(), 26457);
     }
 
     #[test]
     fn test_calc_string_with_precedence() {
-        let ex1 = "1 + 2 * 3 + 4 * 5 + 6".to_string();
-        let ex2 = "1 + (2 * 3) + (4 * (5 + 6))".to_string();
-        let ex3 = "2 * 3 + (4 * 5)".to_string();
-        let ex4 = "5 + (8 * 3 + 9 + 3 * 4 * 3)".to_string();
-        let ex5 = "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))".to_string();
-        let ex6 = "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2".to_string();
-
-        assert_eq!(
-            calc_string_with_precedence(&mut ex1.chars().collect::<Vec<char>>(), &mut 0, false),
-            231
-        );
-        assert_eq!(
-            calc_string_with_precedence(&mut ex2.chars().collect::<Vec<char>>(), &mut 0, false),
-            51
-        );
-        assert_eq!(
-            calc_string_with_precedence(&mut ex3.chars().collect::<Vec<char>>(), &mut 0, false),
-            46
-        );
-        assert_eq!(
-            calc_string_with_precedence(&mut ex4.chars().collect::<Vec<char>>(), &mut 0, false),
-            1445
-        );
-        assert_eq!(
-            calc_string_with_precedence(&mut ex5.chars().collect::<Vec<char>>(), &mut 0, false),
-            669060
-        );
-        assert_eq!(
-            calc_string_with_precedence(&mut ex6.chars().collect::<Vec<char>>(), &mut 0, false),
-            23340
-        );
+        let ex: Vec<Vec<char>> = Problem
+            .parse_str(EXAMPLE)
+            .unwrap()
+            .iter()
+            .map(|l| l.chars().collect())
+            .collect();
+
+        assert_eq!(calc_string_with_precedence(&ex[0], &mut 0, false), 231);
+        assert_eq!(calc_string_with_precedence(&ex[1], &mut 0, false), 51);
+        assert_eq!(calc_string_with_precedence(&ex[2], &mut 0, false), 46);
+        assert_eq!(calc_string_with_precedence(&ex[3], &mut 0, false), 1445);
+        assert_eq!(calc_string_with_precedence(&ex[4], &mut 0, false), 669060);
+        assert_eq!(calc_string_with_precedence(&ex[5], &mut 0, false), 23340);
     }
 }
@@ -1,31 +1,36 @@
+use crate::error::{field, parse_field, parse_token, Error, Result};
 use crate::solver::Solver;
 use itertools::Itertools;
 use pcre2::bytes::Regex;
+use std::collections::HashMap;
+use std::io;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 19, Problem);
+
 impl Solver for Problem {
     type Input = (HashMap<usize, String>, Vec<String>);
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> (HashMap<usize, String>, Vec<String>) {
-        let buf_reader = BufReader::new(file);
-        let lines = buf_reader.lines().map(|l| l.unwrap()).collect();
+    fn parse_input(
+        &self,
+        reader: &mut dyn BufRead,
+    ) -> Result<(HashMap<usize, String>, Vec<String>)> {
+        let lines = reader.lines().collect::<io::Result<Vec<String>>>()?;
         parse_lines(lines)
     }
 
-    fn solve_part_one(&self, input: &(HashMap<usize, String>, Vec<String>)) -> usize {
+    fn solve_part_one(&self, input: &(HashMap<usize, String>, Vec<String>)) -> Result<usize> {
         let regex = generate_regex(0, &input.0, false);
-        validate_messages(regex, &input.1)
+        Ok(validate_messages(regex, &input.1))
     }
 
-    fn solve_part_two(&self, input: &(HashMap<usize, String>, Vec<String>)) -> usize {
+    fn solve_part_two(&self, input: &(HashMap<usize, String>, Vec<String>)) -> Result<usize> {
         let regex = generate_regex(0, &input.0, true);
-        validate_messages(regex, &input.1)
+        Ok(validate_messages(regex, &input.1))
     }
 }
 
@@ -52,7 +57,7 @@ fn generate_regex(
                     let mut follow_regex = String::from("(?:");
                     let rule_number = r.parse::<usize>().unwrap();
                     follow_regex.push_str(&generate_regex(rule_number, rules, with_replace));
-                    follow_regex.push_str(")");
+                    follow_regex.push(')');
                     follow_regex
                 })
                 .collect_vec();
@@ -65,10 +70,10 @@ fn generate_regex(
                 }
             }
 
-            pipe_regex.push_str(")");
+            pipe_regex.push(')');
 
             if with_replace && rule_number == 8 {
-                pipe_regex.push_str("+");
+                pipe_regex.push('+');
             }
 
             pipe_regex
@@ -89,22 +94,34 @@ fn validate_messages(regex: String, messages: &Vec<String>) -> usize {
     valid_messages
 }
 
-fn parse_lines(lines: Vec<String>) -> (HashMap<usize, String>, Vec<String>) {
+fn parse_lines(lines: Vec<String>) -> Result<(HashMap<usize, String>, Vec<String>)> {
     let mut rules = HashMap::new();
     let mut messages = Vec::new();
 
     let mut i = 0;
     loop {
+        if i == lines.len() {
+            return Err(Error::parse(
+                "expected a blank line between rules and messages",
+            ));
+        }
+
         let line = &lines[i];
-        if line == "" {
-            i = i + 1;
+        if line.is_empty() {
+            i += 1;
             break;
         }
 
         let split = line.split(": ").collect::<Vec<&str>>();
-        rules.insert(split[0].parse::<usize>().unwrap(), split[1].to_string());
+        let rule = field(i + 1, line, &split, 1)?;
+        if rule != "\"a\"" && rule != "\"b\"" {
+            for r in rule.split(' ').filter(|r| *r != "|") {
+                parse_token::<usize>(i + 1, line, r)?;
+            }
+        }
+        rules.insert(parse_field(i + 1, line, &split, 0)?, rule.to_string());
 
-        i = i + 1;
+        i += 1;
     }
 
     loop {
@@ -114,10 +131,14 @@ fn parse_lines(lines: Vec<String>) -> (HashMap<usize, String>, Vec<String>) {
 
         messages.push(lines[i].to_string());
 
-        i = i + 1;
+        i += 1;
+    }
+
+    if !rules.contains_key(&0) {
+        return Err(Error::parse("missing rule 0"));
     }
 
-    (rules, messages)
+    Ok((rules, messages))
 }
 
 #[cfg(test)]
@@ -126,70 +147,74 @@ mod tests {
 
     #[test]
     fn test_validate_messages() {
-        let ex1 = parse_lines(vec![
-            "0: 4 1 5".to_string(),
-            "1: 2 3 | 3 2".to_string(),
-            "2: 4 4 | 5 5".to_string(),
-            "3: 4 5 | 5 4".to_string(),
-            "4: \"a\"".to_string(),
-            "5: \"b\"".to_string(),
-            "".to_string(),
-            "ababbb".to_string(),
-            "bababa".to_string(),
-            "abbbab".to_string(),
-            "aaabbb".to_string(),
-            "aaaabbb".to_string(),
-        ]);
-
-        let ex2 = parse_lines(vec![
-            "42: 9 14 | 10 1".to_string(),
-            "9: 14 27 | 1 26".to_string(),
-            "10: 23 14 | 28 1".to_string(),
-            "1: \"a\"".to_string(),
-            "11: 42 31".to_string(),
-            "5: 1 14 | 15 1".to_string(),
-            "19: 14 1 | 14 14".to_string(),
-            "12: 24 14 | 19 1".to_string(),
-            "16: 15 1 | 14 14".to_string(),
-            "31: 14 17 | 1 13".to_string(),
-            "6: 14 14 | 1 14".to_string(),
-            "2: 1 24 | 14 4".to_string(),
-            "0: 8 11".to_string(),
-            "13: 14 3 | 1 12".to_string(),
-            "15: 1 | 14".to_string(),
-            "17: 14 2 | 1 7".to_string(),
-            "23: 25 1 | 22 14".to_string(),
-            "28: 16 1".to_string(),
-            "4: 1 1".to_string(),
-            "20: 14 14 | 1 15".to_string(),
-            "3: 5 14 | 16 1".to_string(),
-            "27: 1 6 | 14 18".to_string(),
-            "14: \"b\"".to_string(),
-            "21: 14 1 | 1 14".to_string(),
-            "25: 1 1 | 1 14".to_string(),
-            "22: 14 14".to_string(),
-            "8: 42".to_string(),
-            "26: 14 22 | 1 20".to_string(),
-            "18: 15 15".to_string(),
-            "7: 14 5 | 1 21".to_string(),
-            "24: 14 1".to_string(),
-            "".to_string(),
-            "abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa".to_string(),
-            "bbabbbbaabaabba".to_string(),
-            "babbbbaabbbbbabbbbbbaabaaabaaa".to_string(),
-            "aaabbbbbbaaaabaababaabababbabaaabbababababaaa".to_string(),
-            "bbbbbbbaaaabbbbaaabbabaaa".to_string(),
-            "bbbababbbbaaaaaaaabbababaaababaabab".to_string(),
-            "ababaaaaaabaaab".to_string(),
-            "ababaaaaabbbaba".to_string(),
-            "baabbaaaabbaaaababbaababb".to_string(),
-            "abbbbabbbbaaaababbbbbbaaaababb".to_string(),
-            "aaaaabbaabaaaaababaa".to_string(),
-            "aaaabbaaaabbaaa".to_string(),
-            "aaaabbaabbaaaaaaabbbabbbaaabbaabaaa".to_string(),
-            "babaaabbbaaabaababbaabababaaab".to_string(),
-            "aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba".to_string(),
-        ]);
+        let ex1 = Problem
+            .parse_str(
+                "0: 4 1 5\n\
+                 1: 2 3 | 3 2\n\
+                 2: 4 4 | 5 5\n\
+                 3: 4 5 | 5 4\n\
+                 4: \"a\"\n\
+                 5: \"b\"\n\
+                 \n\
+                 ababbb\n\
+                 bababa\n\
+                 abbbab\n\
+                 aaabbb\n\
+                 aaaabbb\n",
+            )
+            .unwrap();
+
+        let ex2 = Problem
+            .parse_str(
+                "42: 9 14 | 10 1\n\
+                 9: 14 27 | 1 26\n\
+                 10: 23 14 | 28 1\n\
+                 1: \"a\"\n\
+                 11: 42 31\n\
+                 5: 1 14 | 15 1\n\
+                 19: 14 1 | 14 14\n\
+                 12: 24 14 | 19 1\n\
+                 16: 15 1 | 14 14\n\
+                 31: 14 17 | 1 13\n\
+                 6: 14 14 | 1 14\n\
+                 2: 1 24 | 14 4\n\
+                 0: 8 11\n\
+                 13: 14 3 | 1 12\n\
+                 15: 1 | 14\n\
+                 17: 14 2 | 1 7\n\
+                 23: 25 1 | 22 14\n\
+                 28: 16 1\n\
+                 4: 1 1\n\
+                 20: 14 14 | 1 15\n\
+                 3: 5 14 | 16 1\n\
+                 27: 1 6 | 14 18\n\
+                 14: \"b\"\n\
+                 21: 14 1 | 1 14\n\
+                 25: 1 1 | 1 14\n\
+                 22: 14 14\n\
+                 8: 42\n\
+                 26: 14 22 | 1 20\n\
+                 18: 15 15\n\
+                 7: 14 5 | 1 21\n\
+                 24: 14 1\n\
+                 \n\
+                 abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa\n\
+                 bbabbbbaabaabba\n\
+                 babbbbaabbbbbabbbbbbaabaaabaaa\n\
+                 aaabbbbbbaaaabaababaabababbabaaabbababababaaa\n\
+                 bbbbbbbaaaabbbbaaabbabaaa\n\
+                 bbbababbbbaaaaaaaabbababaaababaabab\n\
+                 ababaaaaaabaaab\n\
+                 ababaaaaabbbaba\n\
+                 baabbaaaabbaaaababbaababb\n\
+                 abbbbabbbbaaaababbbbbbaaaababb\n\
+                 aaaaabbaabaaaaababaa\n\
+                 aaaabbaaaabbaaa\n\
+                 aaaabbaabbaaaaaaabbbabbbaaabbaabaaa\n\
+                 babaaabbbaaabaababbaabababaaab\n\
+                 aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba\n",
+            )
+            .unwrap();
 
         let reg1 = generate_regex(0, &ex1.0, false);
         let reg2_no_replacement = generate_regex(0, &ex2.0, false);
@@ -198,5 +223,7 @@ mod tests {
         assert_eq!(validate_messages(reg1, &ex1.1), 2);
         assert_eq!(validate_messages(reg2_no_replacement, &ex2.1), 3);
         assert_eq!(validate_messages(reg2_with_replacement, &ex2.1), 12);
+        assert_eq!(Problem.solve_part_one(&ex1).unwrap(), 2);
+        assert_eq!(Problem.solve_part_two(&ex2).unwrap(), 12);
     }
 }
@@ -1,11 +1,12 @@
+use crate::error::{parse_token, Error, Result};
+use crate::grid::Grid;
+use crate::parse;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Tile {
     id: usize,
-    data: Vec<Vec<bool>>,
+    data: Grid<bool>,
     rotation: u8, //default = 0, 90dcw = 1, 180dcw = 2; 270dcw = 3
     flipped: bool, //default = false, flipped = true
                   // rotate first then flip
@@ -26,25 +27,25 @@ impl Tile {
     fn get_borders(&self) -> Vec<Vec<bool>> {
         let mut borders = Vec::new();
 
-        let up = self.data[0].clone();
+        let up = self.data.row(0).to_vec();
         let mut up_flipped = up.clone();
         up_flipped.reverse();
         borders.push(up);
         borders.push(up_flipped);
 
-        let right = self.data.iter().map(|d| d[9]).collect::<Vec<bool>>();
+        let right = self.data.column(9);
         let mut right_flipped = right.clone();
         right_flipped.reverse();
         borders.push(right);
         borders.push(right_flipped);
 
-        let down = self.data[9].clone();
+        let down = self.data.row(9).to_vec();
         let mut down_flipped = down.clone();
         down_flipped.reverse();
         borders.push(down);
         borders.push(down_flipped);
 
-        let left = self.data.iter().map(|d| d[0]).collect::<Vec<bool>>();
+        let left = self.data.column(0);
         let mut left_flipped = left.clone();
         left_flipped.reverse();
         borders.push(left);
@@ -53,132 +54,76 @@ impl Tile {
         borders
     }
 
-    fn get_border(&self, side_number: u8) -> Vec<bool> {
-        let mut norm_side_number = (side_number as i8 - self.rotation as i8).rem_euclid(4);
-        norm_side_number = if self.flipped && (side_number == 1 || side_number == 3) {
-            (norm_side_number + 2) % 4
-        } else {
-            norm_side_number
-        };
-
-        let mut border = match norm_side_number {
-            0 => self.data[0].clone(),
-            1 => self.data.iter().map(|d| d[9]).collect::<Vec<bool>>(),
-            2 => {
-                let mut border = self.data[9].clone();
-                border.reverse();
-                border
-            }
-            3 => {
-                let mut border = self.data.iter().map(|d| d[0]).collect::<Vec<bool>>();
-                border.reverse();
-                border
-            }
-            _ => panic!("Invalid side number"),
-        };
+    fn oriented(&self) -> Grid<bool> {
+        let mut data = self.data.clone();
+        for _ in 0..self.rotation {
+            data = data.rotate_clockwise();
+        }
 
         if self.flipped {
-            border.reverse();
+            data.flip_horizontal()
+        } else {
+            data
         }
-
-        border
     }
 
-    fn get_image_without_borders(&self) -> Vec<Vec<bool>> {
-        let mut image = Vec::new();
-        for i in 0..8 {
-            image.push(Vec::new());
-            for _j in 0..8 {
-                image[i].push(false);
-            }
-        }
-
-        match self.rotation {
-            0 => {
-                for i in 1..=8 {
-                    for j in 1..=8 {
-                        if self.flipped {
-                            image[i - 1][j - 1] = self.data[i][9 - j];
-                        } else {
-                            image[i - 1][j - 1] = self.data[i][j];
-                        }
-                    }
-                }
-            }
-            1 => {
-                for i in 1..=8 {
-                    for j in 1..=8 {
-                        if self.flipped {
-                            image[i - 1][j - 1] = self.data[j][i];
-                        } else {
-                            image[i - 1][j - 1] = self.data[9 - j][i];
-                        }
-                    }
-                }
-            }
-            2 => {
-                for i in 1..=8 {
-                    for j in 1..=8 {
-                        if self.flipped {
-                            image[i - 1][j - 1] = self.data[9 - i][j];
-                        } else {
-                            image[i - 1][j - 1] = self.data[9 - i][9 - j];
-                        }
-                    }
-                }
-            }
-            3 => {
-                for i in 1..=8 {
-                    for j in 1..=8 {
-                        if self.flipped {
-                            image[i - 1][j - 1] = self.data[9 - j][9 - i];
-                        } else {
-                            image[i - 1][j - 1] = self.data[j][9 - i];
-                        }
-                    }
-                }
-            }
-            _ => (),
+    // Borders are read clockwise, so matching sides of neighbouring tiles are
+    // the reverse of each other
+    fn get_border(&self, side_number: u8) -> Vec<bool> {
+        let data = self.oriented();
+        match side_number {
+            0 => data.row(0).to_vec(),
+            1 => data.column(9),
+            2 => data.row(9).iter().rev().copied().collect(),
+            3 => data.column(0).into_iter().rev().collect(),
+            _ => panic!("Invalid side number"),
         }
+    }
 
-        image
+    fn get_image_without_borders(&self) -> Grid<bool> {
+        self.oriented().subgrid(1, 1, 8, 8)
     }
 }
 
 pub struct Problem;
 
+register_solver!(2020, 20, Problem);
+
 impl Solver for Problem {
     type Input = Vec<Tile>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<Tile> {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<Tile>> {
         let mut lines = String::new();
-        buf_reader.read_to_string(&mut lines).unwrap();
+        reader.read_to_string(&mut lines)?;
         parse_tiles(lines)
     }
 
-    fn solve_part_one(&self, input: &Vec<Tile>) -> usize {
-        find_corners(input).iter().map(|t| t.id).product()
+    fn solve_part_one(&self, input: &Vec<Tile>) -> Result<usize> {
+        Ok(find_corners(input)?.iter().map(|t| t.id).product())
     }
 
-    fn solve_part_two(&self, input: &Vec<Tile>) -> usize {
-        let image = get_image(&input);
-        let monsters = find_monsters(&image);
-
-        let water_count = image
-            .iter()
-            .map(|r| r.iter().filter(|c| **c).count())
-            .sum::<usize>();
-        let monster_count = monsters.len();
+    fn solve_part_two(&self, input: &Vec<Tile>) -> Result<usize> {
+        let mut image = get_image(input)?;
+        let water_count = image.iter().filter(|c| **c).count();
+
+        // The monsters only show up in one of the eight orientations of the image
+        let mut monster_count = 0;
+        for _ in 0..4 {
+            let flipped = image.flip_horizontal();
+            monster_count = monster_count
+                .max(find_monsters(&image).len())
+                .max(find_monsters(&flipped).len());
+            image = image.rotate_clockwise();
+        }
 
-        water_count - monster_count
+        Ok(water_count - monster_count)
     }
 }
 
-fn find_corners(tiles: &Vec<Tile>) -> Vec<&Tile> {
-    tiles
+fn find_corners(tiles: &Vec<Tile>) -> Result<Vec<&Tile>> {
+    let corners = tiles
         .iter()
         .filter(|t| {
             let mut matches = 0;
@@ -192,20 +137,28 @@ fn find_corners(tiles: &Vec<Tile>) -> Vec<&Tile> {
                     .iter()
                     .any(|b| tile.get_borders().iter().any(|bn| b == bn))
                 {
-                    matches = matches + 1;
+                    matches += 1;
                 }
             }
             matches == 2
         })
-        .collect::<Vec<&Tile>>()
+        .collect::<Vec<&Tile>>();
+
+    if corners.len() != 4 {
+        return Err(Error::solve(format!(
+            "expected 4 corner tiles, found {}",
+            corners.len()
+        )));
+    }
+    Ok(corners)
 }
 
-fn find_first_corner(tiles: &Vec<Tile>) -> Tile {
-    let mut corner = find_corners(tiles)[0].clone();
+fn find_first_corner(tiles: &Vec<Tile>) -> Result<Tile> {
+    let mut corner = find_corners(tiles)?[0].clone();
     for rotation in 0..4 {
         for flip in 0..2 {
             corner.rotation = rotation;
-            corner.flipped = if flip == 0 { false } else { true };
+            corner.flipped = flip != 0;
 
             let right_border = corner.get_border(1);
             let down_border = corner.get_border(2);
@@ -218,26 +171,23 @@ fn find_first_corner(tiles: &Vec<Tile>) -> Tile {
             });
 
             if matches.count() == 2 {
-                return corner;
+                return Ok(corner);
             }
         }
     }
 
-    panic!("No corner found");
+    Err(Error::solve(format!(
+        "no orientation of corner tile {} fits the top left",
+        corner.id
+    )))
 }
 
-fn get_image(tiles: &Vec<Tile>) -> Vec<Vec<bool>> {
-    let mut current_tile = find_first_corner(tiles);
+fn get_image(tiles: &Vec<Tile>) -> Result<Grid<bool>> {
+    let square =
+        side_length(tiles.len()).ok_or_else(|| Error::solve("the tiles do not form a square"))?;
+    let mut current_tile = find_first_corner(tiles)?;
     let mut first_last_row = current_tile.clone();
-    let mut image = Vec::new();
-    let square = (tiles.len() as f32).sqrt() as usize;
-    let image_length = square * 8;
-    for i in 0..image_length {
-        image.push(Vec::new());
-        for _j in 0..image_length {
-            image[i].push(false);
-        }
-    }
+    let mut image = Grid::new(square * 8, square * 8, false);
 
     add_to_image(&mut image, &current_tile, 0, 0);
 
@@ -248,18 +198,18 @@ fn get_image(tiles: &Vec<Tile>) -> Vec<Vec<bool>> {
             }
             if j == 0 {
                 let down_border = first_last_row.get_border(2);
-                current_tile = find_matching_tile(tiles, first_last_row.id, &down_border, 0);
+                current_tile = find_matching_tile(tiles, first_last_row.id, &down_border, 0)?;
                 add_to_image(&mut image, &current_tile, j, i);
                 first_last_row = current_tile.clone();
             } else {
                 let right_border = &current_tile.get_border(1);
-                current_tile = find_matching_tile(tiles, current_tile.id, &right_border, 3);
+                current_tile = find_matching_tile(tiles, current_tile.id, right_border, 3)?;
                 add_to_image(&mut image, &current_tile, j, i);
             }
         }
     }
 
-    image
+    Ok(image)
 }
 
 fn find_matching_tile(
@@ -267,7 +217,7 @@ fn find_matching_tile(
     current_tile_id: usize,
     border: &Vec<bool>,
     side_to_match: u8,
-) -> Tile {
+) -> Result<Tile> {
     for tile in tiles {
         if tile.id == current_tile_id {
             continue;
@@ -276,57 +226,94 @@ fn find_matching_tile(
         for rotation in 0..4 {
             for flip in 0..2 {
                 tile_clone.rotation = rotation;
-                tile_clone.flipped = if flip == 0 { false } else { true };
+                tile_clone.flipped = flip != 0;
 
                 let mut tile_border = tile_clone.get_border(side_to_match);
                 tile_border.reverse();
 
                 if border == &tile_border {
-                    return tile_clone;
+                    return Ok(tile_clone);
                 }
             }
         }
     }
 
-    panic!("No tile found for {}", current_tile_id);
+    Err(Error::solve(format!(
+        "no tile matches the border of tile {}",
+        current_tile_id
+    )))
 }
 
-fn add_to_image(image: &mut Vec<Vec<bool>>, tile: &Tile, tile_x: usize, tile_y: usize) {
+fn add_to_image(image: &mut Grid<bool>, tile: &Tile, tile_x: usize, tile_y: usize) {
     let tile_image = tile.get_image_without_borders();
-    for i in tile_y * 8..tile_y * 8 + 8 {
-        for j in tile_x * 8..tile_x * 8 + 8 {
-            image[i][j] = tile_image[i - tile_y * 8][j - tile_x * 8];
-        }
+    for (x, y) in tile_image.positions() {
+        image[(tile_x * 8 + x, tile_y * 8 + y)] = tile_image[(x, y)];
     }
 }
 
-fn parse_tiles(lines: String) -> Vec<Tile> {
-    lines
-        .split("\n\n")
-        .map(|l| {
-            let tile_lines = l.split("\n").collect::<Vec<&str>>();
-            let id = tile_lines[0][5..9].parse::<usize>().unwrap();
+// The number of tiles along each side of a square image of `tile_count` tiles
+fn side_length(tile_count: usize) -> Option<usize> {
+    let side = (tile_count as f64).sqrt().round() as usize;
+    if side * side == tile_count {
+        Some(side)
+    } else {
+        None
+    }
+}
 
-            let data = tile_lines[1..]
-                .iter()
-                .map(|r| {
-                    r.chars()
-                        .map(|c| if c == '.' { false } else { true })
-                        .collect::<Vec<bool>>()
-                })
-                .collect::<Vec<Vec<bool>>>();
-
-            Tile {
-                id: id,
-                data: data,
+// Tiles are separated by blank lines, each a `Tile <id>:` line and 10 rows
+fn parse_tiles(lines: String) -> Result<Vec<Tile>> {
+    let tiles = parse::paragraphs(&lines)
+        .iter()
+        .map(|paragraph| {
+            let (header_line, header) = paragraph[0];
+            let id = header
+                .strip_prefix("Tile ")
+                .and_then(|l| l.strip_suffix(':'))
+                .ok_or_else(|| Error::parse_at(header_line, 1, "expected 'Tile <id>:'"))?;
+            let rows = &paragraph[1..];
+            if rows.len() != 10 {
+                return Err(Error::parse_at(header_line, 1, "expected 10 rows per tile"));
+            }
+            if let Some(&(line_number, _)) = rows.iter().find(|(_, row)| row.chars().count() != 10)
+            {
+                return Err(Error::parse_at(
+                    line_number,
+                    1,
+                    "expected 10 columns per tile",
+                ));
+            }
+
+            Ok(Tile {
+                id: parse_token(header_line, header, id)?,
+                data: Grid::parse_lines(
+                    rows.iter().copied(),
+                    "expected '.' or '#'",
+                    |c| match c {
+                        '.' => Some(false),
+                        '#' => Some(true),
+                        _ => None,
+                    },
+                )?,
                 rotation: 0,
                 flipped: false,
-            }
+            })
         })
-        .collect::<Vec<Tile>>()
+        .collect::<Result<Vec<Tile>>>()?;
+
+    if tiles.is_empty() {
+        return Err(Error::parse("input is empty"));
+    }
+    if side_length(tiles.len()).is_none() {
+        return Err(Error::parse(format!(
+            "{} tiles do not form a square image",
+            tiles.len()
+        )));
+    }
+    Ok(tiles)
 }
 
-fn find_monsters(image: &Vec<Vec<bool>>) -> Vec<(usize, usize)> {
+fn find_monsters(image: &Grid<bool>) -> Vec<(usize, usize)> {
     let monster_pattern: Vec<(isize, isize)> = vec![
         (0, 1),
         (1, 1),
@@ -346,14 +333,14 @@ fn find_monsters(image: &Vec<Vec<bool>>) -> Vec<(usize, usize)> {
 
     let mut monsters = Vec::new();
 
-    for i in 1..image.len() - 1 {
-        for j in 0..image[i].len() - 20 {
-            if image[i][j] && !monsters.contains(&(i as usize, j as usize)) {
+    for i in 1..image.height().saturating_sub(1) {
+        for j in 0..image.width().saturating_sub(19) {
+            if image[(j, i)] && !monsters.contains(&(i, j)) {
                 let mut possible_monsters = vec![(i, j)];
 
                 let mut found_monster = true;
                 for p in &monster_pattern {
-                    if image[(i as isize + p.0) as usize][(j as isize + p.1) as usize]
+                    if image[((j as isize + p.1) as usize, (i as isize + p.0) as usize)]
                         && !monsters
                             .contains(&((i as isize + p.0) as usize, (j as isize + p.1) as usize))
                     {
@@ -383,119 +370,135 @@ mod tests {
 
     #[test]
     fn test_find_corners() {
-        let ex = parse_tiles(
-            "Tile 2311:\n".to_string()
-                + "..##.#..#.\n"
-                + "##..#.....\n"
-                + "#...##..#.\n"
-                + "####.#...#\n"
-                + "##.##.###.\n"
-                + "##...#.###\n"
-                + ".#.#.#..##\n"
-                + "..#....#..\n"
-                + "###...#.#.\n"
-                + "..###..###\n"
-                + "\n"
-                + "Tile 1951:\n"
-                + "#.##...##.\n"
-                + "#.####...#\n"
-                + ".....#..##\n"
-                + "#...######\n"
-                + ".##.#....#\n"
-                + ".###.#####\n"
-                + "###.##.##.\n"
-                + ".###....#.\n"
-                + "..#.#..#.#\n"
-                + "#...##.#..\n"
-                + "\n"
-                + "Tile 1171:\n"
-                + "####...##.\n"
-                + "#..##.#..#\n"
-                + "##.#..#.#.\n"
-                + ".###.####.\n"
-                + "..###.####\n"
-                + ".##....##.\n"
-                + ".#...####.\n"
-                + "#.##.####.\n"
-                + "####..#...\n"
-                + ".....##...\n"
-                + "\n"
-                + "Tile 1427:\n"
-                + "###.##.#..\n"
-                + ".#..#.##..\n"
-                + ".#.##.#..#\n"
-                + "#.#.#.##.#\n"
-                + "....#...##\n"
-                + "...##..##.\n"
-                + "...#.#####\n"
-                + ".#.####.#.\n"
-                + "..#..###.#\n"
-                + "..##.#..#.\n"
-                + "\n"
-                + "Tile 1489:\n"
-                + "##.#.#....\n"
-                + "..##...#..\n"
-                + ".##..##...\n"
-                + "..#...#...\n"
-                + "#####...#.\n"
-                + "#..#.#.#.#\n"
-                + "...#.#.#..\n"
-                + "##.#...##.\n"
-                + "..##.##.##\n"
-                + "###.##.#..\n"
-                + "\n"
-                + "Tile 2473:\n"
-                + "#....####.\n"
-                + "#..#.##...\n"
-                + "#.##..#...\n"
-                + "######.#.#\n"
-                + ".#...#.#.#\n"
-                + ".#########\n"
-                + ".###.#..#.\n"
-                + "########.#\n"
-                + "##...##.#.\n"
-                + "..###.#.#.\n"
-                + "\n"
-                + "Tile 2971:\n"
-                + "..#.#....#\n"
-                + "#...###...\n"
-                + "#.#.###...\n"
-                + "##.##..#..\n"
-                + ".#####..##\n"
-                + ".#..####.#\n"
-                + "#..#.#..#.\n"
-                + "..####.###\n"
-                + "..#.#.###.\n"
-                + "...#.#.#.#\n"
-                + "\n"
-                + "Tile 2729:\n"
-                + "...#.#.#.#\n"
-                + "####.#....\n"
-                + "..#.#.....\n"
-                + "....#..#.#\n"
-                + ".##..##.#.\n"
-                + ".#.####...\n"
-                + "####.#.#..\n"
-                + "##.####...\n"
-                + "##..#.##..\n"
-                + "#.##...##.\n"
-                + "\n"
-                + "Tile 3079:\n"
-                + "#.#.#####.\n"
-                + ".#..######\n"
-                + "..#.......\n"
-                + "######....\n"
-                + "####.#..#.\n"
-                + ".#...#.##.\n"
-                + "#.#####.##\n"
-                + "..#.###...\n"
-                + "..#.......\n"
-                + "..#.###...",
-        );
+        let ex = Problem
+            .parse_str(
+                "Tile 2311:\n\
+                 ..##.#..#.\n\
+                 ##..#.....\n\
+                 #...##..#.\n\
+                 ####.#...#\n\
+                 ##.##.###.\n\
+                 ##...#.###\n\
+                 .#.#.#..##\n\
+                 ..#....#..\n\
+                 ###...#.#.\n\
+                 ..###..###\n\
+                 \n\
+                 Tile 1951:\n\
+                 #.##...##.\n\
+                 #.####...#\n\
+                 .....#..##\n\
+                 #...######\n\
+                 .##.#....#\n\
+                 .###.#####\n\
+                 ###.##.##.\n\
+                 .###....#.\n\
+                 ..#.#..#.#\n\
+                 #...##.#..\n\
+                 \n\
+                 Tile 1171:\n\
+                 ####...##.\n\
+                 #..##.#..#\n\
+                 ##.#..#.#.\n\
+                 .###.####.\n\
+                 ..###.####\n\
+                 .##....##.\n\
+                 .#...####.\n\
+                 #.##.####.\n\
+                 ####..#...\n\
+                 .....##...\n\
+                 \n\
+                 Tile 1427:\n\
+                 ###.##.#..\n\
+                 .#..#.##..\n\
+                 .#.##.#..#\n\
+                 #.#.#.##.#\n\
+                 ....#...##\n\
+                 ...##..##.\n\
+                 ...#.#####\n\
+                 .#.####.#.\n\
+                 ..#..###.#\n\
+                 ..##.#..#.\n\
+                 \n\
+                 Tile 1489:\n\
+                 ##.#.#....\n\
+                 ..##...#..\n\
+                 .##..##...\n\
+                 ..#...#...\n\
+                 #####...#.\n\
+                 #..#.#.#.#\n\
+                 ...#.#.#..\n\
+                 ##.#...##.\n\
+                 ..##.##.##\n\
+                 ###.##.#..\n\
+                 \n\
+                 Tile 2473:\n\
+                 #....####.\n\
+                 #..#.##...\n\
+                 #.##..#...\n\
+                 ######.#.#\n\
+                 .#...#.#.#\n\
+                 .#########\n\
+                 .###.#..#.\n\
+                 ########.#\n\
+                 ##...##.#.\n\
+                 ..###.#.#.\n\
+                 \n\
+                 Tile 2971:\n\
+                 ..#.#....#\n\
+                 #...###...\n\
+                 #.#.###...\n\
+                 ##.##..#..\n\
+                 .#####..##\n\
+                 .#..####.#\n\
+                 #..#.#..#.\n\
+                 ..####.###\n\
+                 ..#.#.###.\n\
+                 ...#.#.#.#\n\
+                 \n\
+                 Tile 2729:\n\
+                 ...#.#.#.#\n\
+                 ####.#....\n\
+                 ..#.#.....\n\
+                 ....#..#.#\n\
+                 .##..##.#.\n\
+                 .#.####...\n\
+                 ####.#.#..\n\
+                 ##.####...\n\
+                 ##..#.##..\n\
+                 #.##...##.\n\
+                 \n\
+                 Tile 3079:\n\
+                 #.#.#####.\n\
+                 .#..######\n\
+                 ..#.......\n\
+                 ######....\n\
+                 ####.#..#.\n\
+                 .#...#.##.\n\
+                 #.#####.##\n\
+                 ..#.###...\n\
+                 ..#.......\n\
+                 ..#.###...\n",
+            )
+            .unwrap();
 
         assert_eq!(
-            find_corners(&ex).iter().map(|t| t.id).product::<usize>(),
+            find_corners(&ex)
+                .unwrap()
+                .iter()
+                .map(|t| t.id)
+                .product::<usize>(),
             20899048083289
         );
     }
+
+    #[test]
+    fn test_parse_tile_count() {
+        let tile = "Tile 1:\n".to_string() + &"..........\n".repeat(10);
+        assert!(Problem.parse_str("").is_err());
+        assert!(Problem.parse_str(&[tile.as_str(); 2].join("\n")).is_err());
+        let tiles = Problem.parse_str(&[tile.as_str(); 4].join("\n")).unwrap();
+        assert!(Problem.solve_part_one(&tiles).is_err());
+        assert!(Problem.solve_part_two(&tiles).is_err());
+    }
 }
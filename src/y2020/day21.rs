@@ -1,50 +1,50 @@
+use crate::error::{field, Error, Result};
 use crate::solver::Solver;
 use itertools::Itertools;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 21, Problem);
+
 impl Solver for Problem {
     type Input = Vec<(Vec<String>, Vec<String>)>;
     type Output1 = usize;
     type Output2 = String;
 
-    fn parse_input(&self, file: File) -> Vec<(Vec<String>, Vec<String>)> {
-        let buf_reader = BufReader::new(file);
-        buf_reader
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<(Vec<String>, Vec<String>)>> {
+        reader
             .lines()
-            .map(|l| {
-                let line = l.unwrap();
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
                 let ingredient_allergen_split = line.split(" (contains ").collect::<Vec<&str>>();
                 let ingredients = ingredient_allergen_split[0]
                     .split(" ")
                     .map(|i| i.to_string())
                     .collect::<Vec<String>>();
-                let allergens = ingredient_allergen_split[1]
+                let allergen_list = field(i + 1, &line, &ingredient_allergen_split, 1)?;
+                let allergens = allergen_list
+                    .strip_suffix(')')
+                    .ok_or_else(|| {
+                        Error::parse_at(i + 1, line.chars().count(), "expected a closing ')'")
+                    })?
                     .split(", ")
-                    .map(|a| {
-                        let allergen = a.to_string();
-                        if allergen.ends_with(")") {
-                            return allergen[0..allergen.len() - 1].to_string();
-                        } else {
-                            return allergen;
-                        }
-                    })
+                    .map(|a| a.to_string())
                     .collect::<Vec<String>>();
 
-                (ingredients, allergens)
+                Ok((ingredients, allergens))
             })
             .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<(Vec<String>, Vec<String>)>) -> usize {
-        find_ingredients_without_allergens(input)
+    fn solve_part_one(&self, input: &Vec<(Vec<String>, Vec<String>)>) -> Result<usize> {
+        Ok(find_ingredients_without_allergens(input))
     }
 
-    fn solve_part_two(&self, input: &Vec<(Vec<String>, Vec<String>)>) -> String {
-        map_allergens(input).join(",")
+    fn solve_part_two(&self, input: &Vec<(Vec<String>, Vec<String>)>) -> Result<String> {
+        Ok(map_allergens(input).join(","))
     }
 }
 
@@ -155,38 +155,14 @@ mod tests {
 
     #[test]
     fn test_map_allergens() {
-        let ex = vec![
-            (
-                vec![
-                    "mxmxvkd".to_string(),
-                    "kfchds".to_string(),
-                    "sqjhc".to_string(),
-                    "nhms".to_string(),
-                ],
-                vec!["dairy".to_string(), "fish".to_string()],
-            ),
-            (
-                vec![
-                    "trh".to_string(),
-                    "fvjkl".to_string(),
-                    "sbzzf".to_string(),
-                    "mxmxvkd".to_string(),
-                ],
-                vec!["dairy".to_string()],
-            ),
-            (
-                vec!["sqjhc".to_string(), "fvjkl".to_string()],
-                vec!["soy".to_string()],
-            ),
-            (
-                vec![
-                    "sqjhc".to_string(),
-                    "mxmxvkd".to_string(),
-                    "sbzzf".to_string(),
-                ],
-                vec!["fish".to_string()],
-            ),
-        ];
+        let ex = Problem
+            .parse_str(
+                "mxmxvkd kfchds sqjhc nhms (contains dairy, fish)\n\
+                 trh fvjkl sbzzf mxmxvkd (contains dairy)\n\
+                 sqjhc fvjkl (contains soy)\n\
+                 sqjhc mxmxvkd sbzzf (contains fish)\n",
+            )
+            .unwrap();
 
         assert_eq!(find_ingredients_without_allergens(&ex), 5);
         assert_eq!(map_allergens(&ex).join(","), "mxmxvkd,sqjhc,fvjkl");
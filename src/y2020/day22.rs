@@ -1,59 +1,76 @@
+use crate::error::{parse_token, Error, Result};
+use crate::parse;
 use crate::solver::Solver;
-use std::{collections::VecDeque, fs::File};
+use std::collections::VecDeque;
+use std::hash::Hash;
 use std::{
     collections::{hash_map, HashSet},
     hash::Hasher,
     io::prelude::*,
 };
-use std::{hash::Hash, io::BufReader};
 
 pub struct Problem;
 
+register_solver!(2020, 22, Problem);
+
 impl Solver for Problem {
     type Input = (VecDeque<u8>, VecDeque<u8>);
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> (VecDeque<u8>, VecDeque<u8>) {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<(VecDeque<u8>, VecDeque<u8>)> {
         let mut text = String::new();
-        buf_reader.read_to_string(&mut text).unwrap();
-
-        let player_split = text.split("\n\nPlayer 2:\n").collect::<Vec<&str>>();
-
-        let player_1_deck = player_split[0]
-            .split("\n")
-            .skip(1)
-            .map(|c| c.parse::<u8>().unwrap())
-            .collect::<VecDeque<u8>>();
-        let player_2_deck = player_split[1]
-            .split("\n")
-            .map(|c| c.parse::<u8>().unwrap())
-            .collect::<VecDeque<u8>>();
-        (player_1_deck, player_2_deck)
+        reader.read_to_string(&mut text)?;
+
+        // Each deck is a `Player <n>:` line and a card per line, separated by a blank line
+        let mut decks = vec![VecDeque::new(), VecDeque::new()];
+        for paragraph in parse::paragraphs(&text) {
+            let (header_line, header) = paragraph[0];
+            let deck = match header {
+                "Player 1:" => 0,
+                "Player 2:" => 1,
+                _ => {
+                    return Err(Error::parse_at(
+                        header_line,
+                        1,
+                        "expected 'Player 1:' or 'Player 2:'",
+                    ))
+                }
+            };
+            for &(line_number, line) in &paragraph[1..] {
+                decks[deck].push_back(parse_token::<u8>(line_number, line, line)?);
+            }
+        }
+
+        if decks.iter().any(|d| d.is_empty()) {
+            return Err(Error::parse("expected a non-empty deck for both players"));
+        }
+
+        let player_2_deck = decks.pop().unwrap();
+        let player_1_deck = decks.pop().unwrap();
+        Ok((player_1_deck, player_2_deck))
     }
 
-    fn solve_part_one(&self, input: &(VecDeque<u8>, VecDeque<u8>)) -> usize {
-        play_game(input)
+    fn solve_part_one(&self, input: &(VecDeque<u8>, VecDeque<u8>)) -> Result<usize> {
+        Ok(play_game(input))
     }
 
-    fn solve_part_two(&self, input: &(VecDeque<u8>, VecDeque<u8>)) -> usize {
+    fn solve_part_two(&self, input: &(VecDeque<u8>, VecDeque<u8>)) -> Result<usize> {
         let mut player_1_deck = input.0.clone();
         let mut player_2_deck = input.1.clone();
         let result = play_game_recurse(&mut player_1_deck, &mut player_2_deck);
-        let score;
-        if result == 1 {
-            score = calculate_score(&player_1_deck);
+        let score = if result == 1 {
+            calculate_score(&player_1_deck)
         } else {
-            score = calculate_score(&player_2_deck);
-        }
-        score
+            calculate_score(&player_2_deck)
+        };
+        Ok(score)
     }
 }
 
 fn play_game(start_deck: &(VecDeque<u8>, VecDeque<u8>)) -> usize {
-    let mut player_1_deck = VecDeque::from(start_deck.0.clone());
-    let mut player_2_deck = VecDeque::from(start_deck.1.clone());
+    let mut player_1_deck = start_deck.0.clone();
+    let mut player_2_deck = start_deck.1.clone();
 
     loop {
         let play_1 = player_1_deck.pop_front().unwrap();
@@ -67,10 +84,10 @@ fn play_game(start_deck: &(VecDeque<u8>, VecDeque<u8>)) -> usize {
             player_2_deck.push_back(play_1);
         }
 
-        if player_1_deck.len() == 0 {
+        if player_1_deck.is_empty() {
             return calculate_score(&player_2_deck);
         }
-        if player_2_deck.len() == 0 {
+        if player_2_deck.is_empty() {
             return calculate_score(&player_1_deck);
         }
     }
@@ -80,7 +97,7 @@ fn play_game_recurse(player_1_deck: &mut VecDeque<u8>, player_2_deck: &mut VecDe
     let mut rounds = HashSet::new();
 
     loop {
-        if !rounds.insert(hash(&player_1_deck, &player_2_deck)) {
+        if !rounds.insert(hash(player_1_deck, player_2_deck)) {
             return 1;
         }
 
@@ -112,7 +129,7 @@ fn play_game_recurse(player_1_deck: &mut VecDeque<u8>, player_2_deck: &mut VecDe
             player_2_deck.push_back(play_1);
         }
 
-        if player_1_deck.len() == 0 || player_2_deck.len() == 0 {
+        if player_1_deck.is_empty() || player_2_deck.is_empty() {
             return result;
         }
     }
@@ -121,7 +138,7 @@ fn play_game_recurse(player_1_deck: &mut VecDeque<u8>, player_2_deck: &mut VecDe
 fn calculate_score(deck: &VecDeque<u8>) -> usize {
     let mut score = 0;
     for i in 0..deck.len() {
-        score = score + (deck.len() - i) * (deck[i] as usize);
+        score += (deck.len() - i) * (deck[i] as usize);
     }
 
     score
@@ -138,37 +155,33 @@ fn hash(d1: &VecDeque<u8>, d2: &VecDeque<u8>) -> u64 {
 mod tests {
     use crate::y2020::day22::*;
 
+    const EXAMPLE: &str = "Player 1:\n9\n2\n6\n3\n1\n\nPlayer 2:\n5\n8\n4\n7\n10\n";
+
     #[test]
     fn test_play_game() {
-        let ex = (
-            VecDeque::from(vec![9, 2, 6, 3, 1]),
-            VecDeque::from(vec![5, 8, 4, 7, 10]),
-        );
+        let ex = Problem.parse_str(EXAMPLE).unwrap();
 
         assert_eq!(play_game(&ex), 306);
     }
 
     #[test]
     fn test_play_game_recurse() {
-        let ex1 = (
-            VecDeque::from(vec![9, 2, 6, 3, 1]),
-            VecDeque::from(vec![5, 8, 4, 7, 10]),
-        );
-        let ex2 = (
-            VecDeque::from(vec![43, 19]),
-            VecDeque::from(vec![2, 29, 14]),
-        );
+        let ex1 = Problem.parse_str(EXAMPLE).unwrap();
+        let ex2 = Problem
+            .parse_str("Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14\n")
+            .unwrap();
+
         let mut ex1_1 = ex1.0.clone();
         let mut ex1_2 = ex1.1.clone();
         let result = play_game_recurse(&mut ex1_1, &mut ex1_2);
         let _result_no_loop = play_game_recurse(&mut ex2.0.clone(), &mut ex2.1.clone());
-        let score;
-        if result == 1 {
-            score = calculate_score(&ex1_1);
+        let score = if result == 1 {
+            calculate_score(&ex1_1)
         } else {
-            score = calculate_score(&ex1_2);
-        }
+            calculate_score(&ex1_2)
+        };
 
         assert_eq!(score, 291);
+        assert_eq!(Problem.solve_part_two(&ex1).unwrap(), 291);
     }
 }
@@ -1,26 +1,41 @@
+use crate::error::{Error, Result};
 use crate::solver::Solver;
+use std::collections::HashMap;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashMap, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 23, Problem);
+
 impl Solver for Problem {
     type Input = Vec<usize>;
     type Output1 = String;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<usize> {
-        let mut buf_reader = BufReader::new(file);
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<usize>> {
         let mut line = String::new();
-        buf_reader.read_to_string(&mut line).unwrap();
-        line.chars()
-            .map(|c| c.to_string().parse::<usize>().unwrap())
-            .collect()
+        reader.read_to_string(&mut line)?;
+        let cups = line
+            .trim_end()
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                c.to_digit(10)
+                    .filter(|d| *d > 0)
+                    .map(|d| d as usize)
+                    .ok_or_else(|| Error::parse_at(1, i + 1, format!("invalid cup label '{}'", c)))
+            })
+            .collect::<Result<Vec<usize>>>()?;
+
+        if cups.len() != 9 {
+            return Err(Error::parse_at(1, 1, "expected 9 cups"));
+        }
+
+        Ok(cups)
     }
 
-    fn solve_part_one(&self, input: &Vec<usize>) -> String {
-        let mut cups = create_map_part_1(&input);
+    fn solve_part_one(&self, input: &Vec<usize>) -> Result<String> {
+        let mut cups = create_map_part_1(input);
         play_game(&mut cups, input[0], 100);
 
         let mut result = String::new();
@@ -30,18 +45,18 @@ impl Solver for Problem {
             i = cups[&i];
         }
 
-        result
+        Ok(result)
     }
 
-    fn solve_part_two(&self, input: &Vec<usize>) -> usize {
-        let mut cups = create_map_part_2(&input);
+    fn solve_part_two(&self, input: &Vec<usize>) -> Result<usize> {
+        let mut cups = create_map_part_2(input);
         play_game(&mut cups, input[0], 10000000);
 
-        cups[&1] * cups[&cups[&1]]
+        Ok(cups[&1] * cups[&cups[&1]])
     }
 }
 
-fn create_map_part_1(input: &Vec<usize>) -> HashMap<usize, usize> {
+fn create_map_part_1(input: &[usize]) -> HashMap<usize, usize> {
     let mut cups = HashMap::new();
     for (i, c) in input.iter().enumerate() {
         cups.insert(*c, input[(i + 1) % input.len()]);
@@ -49,7 +64,7 @@ fn create_map_part_1(input: &Vec<usize>) -> HashMap<usize, usize> {
     cups
 }
 
-fn create_map_part_2(input: &Vec<usize>) -> HashMap<usize, usize> {
+fn create_map_part_2(input: &[usize]) -> HashMap<usize, usize> {
     let mut cups = HashMap::new();
     for i in 1..1000000 {
         if i == 9 {
@@ -79,7 +94,7 @@ fn play_game(cups: &mut HashMap<usize, usize>, first_cup: usize, rounds: usize)
 
         let mut destination = current_cup;
         loop {
-            destination = destination - 1;
+            destination -= 1;
             if destination == 0 {
                 destination = cups.len();
             }
@@ -103,7 +118,7 @@ mod tests {
 
     #[test]
     fn test_play_game() {
-        let ex1 = vec![3, 8, 9, 1, 2, 5, 4, 6, 7];
+        let ex1 = Problem.parse_str("389125467\n").unwrap();
         let mut map1 = create_map_part_1(&ex1);
         play_game(&mut map1, 3, 100);
 
@@ -114,7 +129,7 @@ mod tests {
             i = map1[&i];
         }
 
-        let ex2 = vec![3, 8, 9, 1, 2, 5, 4, 6, 7];
+        let ex2 = Problem.parse_str("389125467\n").unwrap();
         let mut map2 = create_map_part_2(&ex2);
         play_game(&mut map2, 3, 10000000);
 
@@ -1,31 +1,51 @@
+use crate::automaton::{Automaton, Rule, SparseAutomaton};
+use crate::error::{Error, Result};
 use crate::solver::Solver;
 use regex::Regex;
+use std::collections::HashSet;
 use std::io::prelude::*;
-use std::io::BufReader;
-use std::{collections::HashSet, fs::File};
 
 pub struct Problem;
 
+register_solver!(2020, 24, Problem);
+
 impl Solver for Problem {
     type Input = Vec<String>;
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> Vec<String> {
-        let buf_reader = BufReader::new(file);
-        buf_reader.lines().map(|l| l.unwrap()).collect()
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<Vec<String>> {
+        reader
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let line = l?;
+                let mut index = 0;
+                while index < line.len() {
+                    let rest = &line[index..];
+                    if rest.starts_with('e') || rest.starts_with('w') {
+                        index += 1;
+                    } else if ["ne", "nw", "se", "sw"].iter().any(|d| rest.starts_with(d)) {
+                        index += 2;
+                    } else {
+                        return Err(Error::parse_at(i + 1, index + 1, "expected a direction"));
+                    }
+                }
+                Ok(line)
+            })
+            .collect()
     }
 
-    fn solve_part_one(&self, input: &Vec<String>) -> usize {
-        find_black_tiles(input).len()
+    fn solve_part_one(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(find_black_tiles(input).len())
     }
 
-    fn solve_part_two(&self, input: &Vec<String>) -> usize {
-        simulate_days(input, 100).len()
+    fn solve_part_two(&self, input: &Vec<String>) -> Result<usize> {
+        Ok(simulate_days(input, 100).len())
     }
 }
 
-fn find_tile_from_instructions(instruction: &String) -> (isize, isize) {
+fn find_tile_from_instructions(instruction: &str) -> (isize, isize) {
     let re = Regex::new(r"nw|ne|se|sw|w|e").unwrap();
     let mut tile_coordinate = (0, 0);
     for result in re.find_iter(instruction) {
@@ -59,41 +79,16 @@ fn find_black_tiles(instructions: &Vec<String>) -> HashSet<(isize, isize)> {
     black_tiles
 }
 
+// A black tile stays black with one or two black neighbours, and a white tile
+// turns black with exactly two
 fn simulate_days(instructions: &Vec<String>, days: usize) -> HashSet<(isize, isize)> {
-    let mut tiles = find_black_tiles(instructions);
-    for _i in 0..days {
-        tiles = change_tiles(&tiles);
-    }
-
-    tiles
-}
-
-fn change_tiles(black_tiles: &HashSet<(isize, isize)>) -> HashSet<(isize, isize)> {
-    let mut new_black_tiles = HashSet::new();
-    for black_tile in black_tiles {
-        let adjacent_tiles = get_adjacent(black_tile);
-
-        let number_adjacent_black_tiles = adjacent_tiles
-            .iter()
-            .filter(|a| black_tiles.contains(a))
-            .count();
-        if !(number_adjacent_black_tiles == 0 || number_adjacent_black_tiles > 2) {
-            new_black_tiles.insert(*black_tile);
-        }
-
-        for adjacent_white_tile in adjacent_tiles.iter().filter(|a| !black_tiles.contains(a)) {
-            let adjacent_to_white_tile = get_adjacent(adjacent_white_tile);
-            if adjacent_to_white_tile
-                .iter()
-                .filter(|a| black_tiles.contains(a))
-                .count()
-                == 2
-            {
-                new_black_tiles.insert(*adjacent_white_tile);
-            }
-        }
-    }
-    new_black_tiles
+    let rule = Rule {
+        birth: 2..=2,
+        survive: 1..=2,
+    };
+    let mut tiles = SparseAutomaton::new(find_black_tiles(instructions), get_adjacent, rule);
+    tiles.run(days);
+    tiles.into_cells()
 }
 
 fn get_adjacent(coordinate: &(isize, isize)) -> Vec<(isize, isize)> {
@@ -113,30 +108,33 @@ mod tests {
 
     #[test]
     fn test_find_black_tiles() {
-        let ex = vec![
-            String::from("sesenwnenenewseeswwswswwnenewsewsw"),
-            String::from("neeenesenwnwwswnenewnwwsewnenwseswesw"),
-            String::from("seswneswswsenwwnwse"),
-            String::from("nwnwneseeswswnenewneswwnewseswneseene"),
-            String::from("swweswneswnenwsewnwneneseenw"),
-            String::from("eesenwseswswnenwswnwnwsewwnwsene"),
-            String::from("sewnenenenesenwsewnenwwwse"),
-            String::from("wenwwweseeeweswwwnwwe"),
-            String::from("wsweesenenewnwwnwsenewsenwwsesesenwne"),
-            String::from("neeswseenwwswnwswswnw"),
-            String::from("nenwswwsewswnenenewsenwsenwnesesenew"),
-            String::from("enewnwewneswsewnwswenweswnenwsenwsw"),
-            String::from("sweneswneswneneenwnewenewwneswswnese"),
-            String::from("swwesenesewenwneswnwwneseswwne"),
-            String::from("enesenwswwswneneswsenwnewswseenwsese"),
-            String::from("wnwnesenesenenwwnenwsewesewsesesew"),
-            String::from("nenewswnwewswnenesenwnesewesw"),
-            String::from("eneswnwswnwsenenwnwnwwseeswneewsenese"),
-            String::from("neswnwewnwnwseenwseesewsenwsweewe"),
-            String::from("wseweeenwnesenwwwswnew"),
-        ];
+        let ex = Problem
+            .parse_str(
+                "sesenwnenenewseeswwswswwnenewsewsw\n\
+                 neeenesenwnwwswnenewnwwsewnenwseswesw\n\
+                 seswneswswsenwwnwse\n\
+                 nwnwneseeswswnenewneswwnewseswneseene\n\
+                 swweswneswnenwsewnwneneseenw\n\
+                 eesenwseswswnenwswnwnwsewwnwsene\n\
+                 sewnenenenesenwsewnenwwwse\n\
+                 wenwwweseeeweswwwnwwe\n\
+                 wsweesenenewnwwnwsenewsenwwsesesenwne\n\
+                 neeswseenwwswnwswswnw\n\
+                 nenwswwsewswnenenewsenwsenwnesesenew\n\
+                 enewnwewneswsewnwswenweswnenwsenwsw\n\
+                 sweneswneswneneenwnewenewwneswswnese\n\
+                 swwesenesewenwneswnwwneseswwne\n\
+                 enesenwswwswneneswsenwnewswseenwsese\n\
+                 wnwnesenesenenwwnenwsewesewsesesew\n\
+                 nenewswnwewswnenesenwnesewesw\n\
+                 eneswnwswnwsenenwnwnwwseeswneewsenese\n\
+                 neswnwewnwnwseenwseesewsenwsweewe\n\
+                 wseweeenwnesenwwwswnew\n",
+            )
+            .unwrap();
 
         assert_eq!(find_black_tiles(&ex).len(), 10);
         assert_eq!(simulate_days(&ex, 100).len(), 2208);
+        assert_eq!(Problem.solve_part_one(&ex).unwrap(), 10);
     }
 }
@@ -1,54 +1,52 @@
+use crate::error::{parse_token, Error, Result};
+use crate::math;
 use crate::solver::Solver;
-use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 pub struct Problem;
 
+register_solver!(2020, 25, Problem);
+
 impl Solver for Problem {
     type Input = (usize, usize);
     type Output1 = usize;
     type Output2 = usize;
 
-    fn parse_input(&self, file: File) -> (usize, usize) {
-        let buf_reader = BufReader::new(file);
-        let mut lines = buf_reader.lines();
-        (
-            lines.nth(0).unwrap().unwrap().parse::<usize>().unwrap(),
-            lines.nth(0).unwrap().unwrap().parse::<usize>().unwrap(),
-        )
+    fn parse_input(&self, reader: &mut dyn BufRead) -> Result<(usize, usize)> {
+        let mut keys = Vec::new();
+        for (i, l) in reader.lines().enumerate().take(2) {
+            let line = l?;
+            keys.push(parse_token::<usize>(i + 1, &line, &line)?);
+        }
+
+        if keys.len() != 2 {
+            return Err(Error::parse("expected two public keys"));
+        }
+
+        Ok((keys[0], keys[1]))
     }
 
-    fn solve_part_one(&self, input: &(usize, usize)) -> usize {
-        let loop_size_card = find_loop_size(input.0);
-        transform_subject_number(input.1, loop_size_card)
+    fn solve_part_one(&self, input: &(usize, usize)) -> Result<usize> {
+        let loop_size_card = find_loop_size(input.0)?;
+        Ok(transform_subject_number(input.1, loop_size_card))
     }
 
-    fn solve_part_two(&self, _input: &(usize, usize)) -> usize {
-        0
+    fn solve_part_two(&self, _input: &(usize, usize)) -> Result<usize> {
+        Ok(0)
     }
 }
 
-fn find_loop_size(public_key: usize) -> usize {
-    let mut i = 1;
-    let mut value = 1;
-    loop {
-        value = (value * 7) % 20201227;
-        if value == public_key {
-            break;
-        }
+const MODULUS: u64 = 20201227;
+const SUBJECT_NUMBER: u64 = 7;
 
-        i = i + 1;
-    }
-    i
+fn find_loop_size(public_key: usize) -> Result<usize> {
+    math::discrete_log(SUBJECT_NUMBER, public_key as u64, MODULUS)
+        .map(|loop_size| loop_size as usize)
+        .ok_or_else(|| Error::solve(format!("no loop size gives public key {}", public_key)))
 }
 
 fn transform_subject_number(subject_number: usize, loop_size: usize) -> usize {
-    let mut value = 1;
-    for _i in 0..loop_size {
-        value = (value * subject_number) % 20201227;
-    }
-    value
+    math::mod_pow(subject_number as u64, loop_size as u64, MODULUS).unwrap() as usize
 }
 
 #[cfg(test)]
@@ -57,10 +55,12 @@ mod tests {
 
     #[test]
     fn test_find_loop_size() {
-        assert_eq!(find_loop_size(5764801), 8);
-        assert_eq!(find_loop_size(17807724), 11);
+        let keys = Problem.parse_str("5764801\n17807724\n").unwrap();
 
+        assert_eq!(find_loop_size(keys.0).unwrap(), 8);
+        assert_eq!(find_loop_size(keys.1).unwrap(), 11);
         assert_eq!(transform_subject_number(17807724, 8), 14897079);
         assert_eq!(transform_subject_number(5764801, 11), 14897079);
+        assert_eq!(Problem.solve_part_one(&keys).unwrap(), 14897079);
     }
 }